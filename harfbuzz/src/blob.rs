@@ -7,12 +7,18 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::any::Any;
+use std::borrow::Cow;
+use std::ffi::CString;
 use std::marker::PhantomData;
 use std::os::raw::{c_char, c_uint, c_void};
+use std::path::Path;
 use std::sync::Arc;
 use std::{mem, ops, ptr, slice};
 use sys;
 
+use crate::Error;
+
 /// Blobs wrap a chunk of binary data to handle lifecycle management of data
 /// while it is passed between client and HarfBuzz.
 ///
@@ -86,6 +92,208 @@ impl<'a> Blob<'a> {
         }
     }
 
+    /// Create a blob with the contents of the file at `path`.
+    ///
+    /// The file is memory-mapped where the platform supports it, and read
+    /// into memory otherwise.
+    ///
+    /// ```
+    /// # use harfbuzz::Blob;
+    /// let path = std::env::temp_dir().join("harfbuzz-blob-from-file");
+    /// std::fs::write(&path, b"OTTO").unwrap();
+    /// let blob = Blob::from_file(&path).unwrap();
+    /// assert_eq!(&*blob, b"OTTO");
+    /// # std::fs::remove_file(&path).unwrap();
+    ///
+    /// assert!(Blob::from_file("/nonexistent/font.ttf").is_err());
+    /// ```
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Blob<'static>, Error> {
+        let path = path.as_ref();
+        let error = || Error::ReadFile(path.to_owned());
+
+        #[cfg(unix)]
+        let name = {
+            use std::os::unix::ffi::OsStrExt;
+            path.as_os_str().as_bytes()
+        };
+        #[cfg(not(unix))]
+        let name = path.to_str().ok_or_else(error)?.as_bytes();
+
+        let name = CString::new(name).map_err(|_| error())?;
+        let raw = unsafe { sys::hb_blob_create_from_file_or_fail(name.as_ptr()) };
+        if raw.is_null() {
+            Err(error())
+        } else {
+            Ok(unsafe { Blob::from_raw(raw) })
+        }
+    }
+
+    /// Create a blob that takes ownership of `data`, such as a
+    /// memory-mapped file.
+    ///
+    /// The data is not copied, and is dropped when the blob and all
+    /// references to it are. See [`from_owned`](#method.from_owned) for
+    /// details.
+    ///
+    /// ```
+    /// # use harfbuzz::Blob;
+    /// let data: Box<[u8]> = vec![1; 256].into_boxed_slice();
+    /// let blob = Blob::from_storage(data);
+    /// assert_eq!(blob.len(), 256);
+    /// ```
+    pub fn from_storage<T>(data: T) -> Blob<'static>
+    where
+        T: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        Blob::from_owned(data)
+    }
+
+    /// Create a blob that takes ownership of `data`, without copying it.
+    ///
+    /// The data is dropped when the blob and all references to it are.
+    ///
+    /// When the blob holds the only reference to the data, HarfBuzz is
+    /// told it is writable, so writing through `DerefMut` does not copy
+    /// it. That is the case for `Vec<u8>`, `Box<[u8]>`, an owned
+    /// `Cow<'static, [u8]>`, an `Arc<[u8]>` without other references,
+    /// and, with the `bytes` feature, `BytesMut` and a unique `Bytes`.
+    /// Other data is read-only.
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use harfbuzz::Blob;
+    /// let data = vec![1, 2, 3];
+    /// let ptr = data.as_ptr();
+    /// let mut blob = Blob::from_owned(data);
+    /// blob[0] = 7;
+    /// assert_eq!(&*blob, &[7, 2, 3]);
+    /// assert_eq!(blob.as_ptr(), ptr);
+    ///
+    /// // Shared data is copied before it is written to.
+    /// let data: Arc<[u8]> = Arc::from(&[1, 2, 3][..]);
+    /// let mut blob = Blob::from_owned(data.clone());
+    /// blob[0] = 7;
+    /// assert_eq!(&*data, &[1, 2, 3]);
+    /// assert_ne!(blob.as_ptr(), data.as_ptr());
+    /// ```
+    pub fn from_owned<T>(data: T) -> Blob<'static>
+    where
+        T: AsRef<[u8]> + 'static,
+    {
+        #[cfg(feature = "bytes")]
+        let data = {
+            let mut data = data;
+            if let Some(bytes) = (&mut data as &mut dyn Any).downcast_mut::<bytes::Bytes>() {
+                match mem::take(bytes).try_into_mut() {
+                    Ok(unique) => return Blob::from_owned(unique),
+                    Err(shared) => *bytes = shared,
+                }
+            }
+            data
+        };
+
+        unsafe extern "C" fn destroy<T>(user_data: *mut c_void) {
+            drop(Box::from_raw(user_data as *mut T))
+        }
+
+        // Box first, so that data stored inline in `T` does not move.
+        let mut data = Box::new(data);
+        let (ptr, len, mode) = match unique_data_mut(&mut *data) {
+            Some(bytes) => (bytes.as_ptr(), bytes.len(), sys::HB_MEMORY_MODE_WRITABLE),
+            None => {
+                let bytes = (*data).as_ref();
+                (bytes.as_ptr(), bytes.len(), sys::HB_MEMORY_MODE_READONLY)
+            }
+        };
+        assert!(len <= c_uint::MAX as usize);
+        unsafe {
+            Blob::from_raw(sys::hb_blob_create(
+                ptr as *const c_char,
+                len as c_uint,
+                mode,
+                Box::into_raw(data) as *mut c_void,
+                Some(destroy::<T>),
+            ))
+        }
+    }
+
+    /// The empty blob.
+    ///
+    /// It is a shared, immutable singleton, so creating it never
+    /// allocates.
+    ///
+    /// ```
+    /// # use harfbuzz::Blob;
+    /// let blob = Blob::empty();
+    /// assert!(blob.is_empty() && blob.is_immutable());
+    /// assert_eq!(&*blob, &[]);
+    /// ```
+    pub fn empty() -> Blob<'static> {
+        unsafe { Blob::from_raw(sys::hb_blob_get_empty()) }
+    }
+
+    /// A blob for the bytes `range` of this blob, sharing its data.
+    ///
+    /// This makes the blob immutable, since changes to it would show
+    /// through the sub-blob.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds, like slicing does.
+    ///
+    /// ```
+    /// # use harfbuzz::Blob;
+    /// let data = b"0123456789";
+    /// let blob = Blob::new_read_only(data);
+    /// let sub = blob.sub_blob(2..5);
+    /// assert_eq!(&*sub, b"234");
+    /// assert!(blob.is_immutable());
+    /// ```
+    pub fn sub_blob(&self, range: ops::Range<usize>) -> Blob<'a> {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range {:?} out of bounds for blob of length {}",
+            range,
+            self.len()
+        );
+        unsafe {
+            Blob::from_raw(sys::hb_blob_create_sub_blob(
+                self.raw,
+                range.start as c_uint,
+                range.len() as c_uint,
+            ))
+        }
+    }
+
+    /// A writable copy of the data of this blob.
+    ///
+    /// Unlike writing through `DerefMut`, this works for immutable blobs
+    /// too, and never changes this blob. Returns [`Error::NoMemory`] if
+    /// the copy could not be allocated.
+    ///
+    /// ```
+    /// # use harfbuzz::Blob;
+    /// let data = [1, 2, 3];
+    /// let mut blob = Blob::new_read_only(&data);
+    /// blob.make_immutable();
+    ///
+    /// let mut copy = blob.to_writable().unwrap();
+    /// copy[0] = 7;
+    /// assert_eq!(&*copy, &[7, 2, 3]);
+    /// assert_eq!(&*blob, &[1, 2, 3]);
+    /// ```
+    pub fn to_writable(&self) -> Result<Blob<'static>, Error> {
+        if self.is_empty() {
+            return Ok(Blob::empty());
+        }
+        let raw = unsafe { sys::hb_blob_copy_writable_or_fail(self.raw) };
+        if raw.is_null() {
+            Err(Error::NoMemory)
+        } else {
+            Ok(unsafe { Blob::from_raw(raw) })
+        }
+    }
+
     /// Construct a `Blob` from a raw pointer. Takes ownership of the blob.
     pub unsafe fn from_raw(raw: *mut sys::hb_blob_t) -> Self {
         Blob {
@@ -96,11 +304,7 @@ impl<'a> Blob<'a> {
 
     /// Fetches the data from this blob.
     pub fn data(&self) -> &[u8] {
-        unsafe {
-            let mut length = 0;
-            let data = sys::hb_blob_get_data(self.raw, &mut length) as *const u8;
-            slice::from_raw_parts(data, length as usize)
-        }
+        self
     }
 
     /// Returns the size of the blob in bytes.
@@ -143,6 +347,33 @@ impl<'a> Blob<'a> {
     }
 }
 
+/// The data of `data` if it is not shared with anything else, for the
+/// owned types [`Blob::from_owned`] knows about.
+fn unique_data_mut(data: &mut dyn Any) -> Option<&mut [u8]> {
+    if data.is::<Vec<u8>>() {
+        return data.downcast_mut::<Vec<u8>>().map(|vec| &mut vec[..]);
+    }
+    if data.is::<Box<[u8]>>() {
+        return data.downcast_mut::<Box<[u8]>>().map(|boxed| &mut boxed[..]);
+    }
+    if data.is::<Cow<'static, [u8]>>() {
+        return match data.downcast_mut::<Cow<'static, [u8]>>() {
+            Some(Cow::Owned(vec)) => Some(vec),
+            _ => None,
+        };
+    }
+    if data.is::<Arc<[u8]>>() {
+        return data.downcast_mut::<Arc<[u8]>>().and_then(Arc::get_mut);
+    }
+    #[cfg(feature = "bytes")]
+    if data.is::<bytes::BytesMut>() {
+        return data
+            .downcast_mut::<bytes::BytesMut>()
+            .map(|bytes| &mut bytes[..]);
+    }
+    None
+}
+
 impl<'a> ops::Deref for Blob<'a> {
     type Target = [u8];
 
@@ -150,6 +381,9 @@ impl<'a> ops::Deref for Blob<'a> {
         unsafe {
             let mut len = 0;
             let ptr = sys::hb_blob_get_data(self.raw, &mut len);
+            if len == 0 {
+                return &[];
+            }
             assert!(!ptr.is_null(), "hb_blob_get_data failed");
             slice::from_raw_parts(ptr as *const u8, len as usize)
         }
@@ -157,7 +391,14 @@ impl<'a> ops::Deref for Blob<'a> {
 }
 
 impl<'a> ops::DerefMut for Blob<'a> {
+    /// Make the data writable, copying it if it was borrowed read-only.
+    ///
+    /// Panics if the blob is immutable or the copy fails; see
+    /// [`Blob::to_writable`] for a fallible alternative.
     fn deref_mut(&mut self) -> &mut [u8] {
+        if self.is_empty() {
+            return &mut [];
+        }
         unsafe {
             let mut len = 0;
             let ptr = sys::hb_blob_get_data_writable(self.raw, &mut len);
@@ -8,23 +8,49 @@
 // except according to those terms.
 
 use std;
+use std::ffi::{CStr, CString};
+use std::mem::ManuallyDrop;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
 use sys;
 
-use crate::{Codepoint, Direction, Language};
+use crate::{
+    ClusterLevel, Codepoint, Direction, Error, Feature, Font, FontMut, Language, Script,
+    SegmentProperties, SerializeFlags, SerializeFormat,
+};
 
+/// Information about a glyph, or a character before shaping.
 #[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct GlyphInfo(sys::hb_glyph_info_t);
 impl GlyphInfo {
+    /// The Unicode codepoint before shaping, or the glyph ID after shaping.
     pub fn codepoint(&self) -> u32 {
         self.0.codepoint
     }
+    /// The index of the character in the original text that corresponds
+    /// to this glyph.
     pub fn cluster(&self) -> u32 {
         self.0.cluster
     }
+    /// The feature mask of the glyph.
     pub fn mask(&self) -> u32 {
         self.0.mask
     }
+    /// The flags set on the glyph by shaping.
+    pub fn flags(&self) -> GlyphFlags {
+        GlyphFlags::from_bits_truncate(unsafe { sys::hb_glyph_info_get_glyph_flags(&self.0) })
+    }
+    /// Whether breaking the text at the beginning of this glyph's cluster
+    /// requires reshaping both sides.
+    pub fn unsafe_to_break(&self) -> bool {
+        self.flags().contains(GlyphFlags::UNSAFE_TO_BREAK)
+    }
+    /// Whether changing the text on one side of the beginning of this
+    /// glyph's cluster may change the shaping of the other side.
+    pub fn unsafe_to_concat(&self) -> bool {
+        self.flags().contains(GlyphFlags::UNSAFE_TO_CONCAT)
+    }
 }
 
 impl std::fmt::Debug for GlyphInfo {
@@ -39,22 +65,51 @@ impl std::fmt::Debug for GlyphInfo {
     }
 }
 
+/// The position of a glyph, relative to the current point.
 #[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct GlyphPosition(sys::hb_glyph_position_t);
 impl GlyphPosition {
+    /// How much the line advances after drawing this glyph when setting
+    /// text in horizontal direction.
     pub fn x_advance(&self) -> i32 {
         self.0.x_advance
     }
+    /// How much the line advances after drawing this glyph when setting
+    /// text in vertical direction.
     pub fn y_advance(&self) -> i32 {
         self.0.y_advance
     }
+    /// How much the glyph moves on the X-axis before drawing it.
     pub fn x_offset(&self) -> i32 {
         self.0.x_offset
     }
+    /// How much the glyph moves on the Y-axis before drawing it.
     pub fn y_offset(&self) -> i32 {
         self.0.y_offset
     }
+
+    /// Set how much the line advances after drawing this glyph when setting
+    /// text in horizontal direction.
+    pub fn set_x_advance(&mut self, x_advance: i32) {
+        self.0.x_advance = x_advance;
+    }
+
+    /// Set how much the line advances after drawing this glyph when setting
+    /// text in vertical direction.
+    pub fn set_y_advance(&mut self, y_advance: i32) {
+        self.0.y_advance = y_advance;
+    }
+
+    /// Set how much the glyph moves on the X-axis before drawing it.
+    pub fn set_x_offset(&mut self, x_offset: i32) {
+        self.0.x_offset = x_offset;
+    }
+
+    /// Set how much the glyph moves on the Y-axis before drawing it.
+    pub fn set_y_offset(&mut self, y_offset: i32) {
+        self.0.y_offset = y_offset;
+    }
 }
 
 impl std::fmt::Debug for GlyphPosition {
@@ -113,11 +168,11 @@ impl std::fmt::Debug for GlyphPosition {
 /// properties using the [`guess_segment_properties`] method on `Buffer`:
 ///
 /// ```
-/// # use harfbuzz::{Buffer, Direction, sys};
+/// # use harfbuzz::{Buffer, Direction, Script};
 /// let mut b = Buffer::with("مساء الخير");
 /// b.guess_segment_properties();
 /// assert_eq!(b.get_direction(), Direction::RTL);
-/// assert_eq!(b.get_script(), sys::HB_SCRIPT_ARABIC);
+/// assert_eq!(b.get_script(), Script::ARABIC);
 /// ```
 ///
 /// [`set_direction`]: #method.set_direction
@@ -181,18 +236,263 @@ impl Buffer {
     }
 
     /// Add UTF-8 encoded text to the buffer.
+    ///
+    /// Only the `length` bytes starting at `start_at` are added, or all of
+    /// the text from `start_at` on if `length` is `None`. The text around
+    /// that item is used as context for shaping, and the cluster of each
+    /// added character is its byte index in `text`.
+    ///
+    /// Panics if the item is out of bounds of `text` or does not start and
+    /// end on character boundaries; use [`add_item`](#method.add_item)
+    /// with a [`TextItem`] to check that beforehand.
     pub fn add_str(&mut self, text: &str, start_at: usize, length: Option<usize>) {
+        let (text_length, item_offset, item_length) = item_args(text.len(), start_at, length);
+        let end = length.map_or(text.len(), |length| start_at + length);
+        assert!(
+            text.is_char_boundary(start_at) && text.is_char_boundary(end),
+            "item {}..{} is not on character boundaries",
+            start_at,
+            end
+        );
         unsafe {
             sys::hb_buffer_add_utf8(
                 self.raw,
                 text.as_ptr() as *const std::os::raw::c_char,
-                text.len() as std::os::raw::c_int,
-                start_at as std::os::raw::c_uint,
-                length.map(|l| l as std::os::raw::c_int).unwrap_or(-1),
+                text_length,
+                item_offset,
+                item_length,
+            )
+        };
+    }
+
+    /// Add an item of a larger text to the buffer.
+    ///
+    /// Unlike [`add_str`](#method.add_str), this does not panic, since
+    /// the item boundaries were checked when the [`TextItem`] was
+    /// created.
+    ///
+    /// ```
+    /// # use harfbuzz::{Buffer, TextItem};
+    /// let paragraph = "one مساء two";
+    /// let item = TextItem::new(paragraph, 4..12).unwrap();
+    ///
+    /// let mut b = Buffer::new();
+    /// b.add_item(&item);
+    /// assert_eq!(b.len(), item.item().chars().count());
+    /// assert_eq!(b.glyph_infos()[0].cluster(), 4);
+    /// ```
+    pub fn add_item(&mut self, item: &TextItem) {
+        let range = item.range();
+        self.add_str(item.text(), range.start, Some(range.len()));
+    }
+
+    /// Add UTF-16 encoded text to the buffer.
+    ///
+    /// `start_at` and `length` select the item to add as for
+    /// [`add_str`](#method.add_str), and clusters are indices of code
+    /// units in `text`. Unpaired surrogates are replaced by the
+    /// [`replacement_codepoint`](#method.replacement_codepoint).
+    ///
+    /// Panics if the item is out of bounds of `text`, or `text` is longer
+    /// than `i32::MAX`.
+    ///
+    /// ```
+    /// # use harfbuzz::Buffer;
+    /// let text: Vec<u16> = "a😀b".encode_utf16().collect();
+    /// let mut b = Buffer::new();
+    /// b.add_utf16(&text, 0, None);
+    /// let clusters: Vec<_> = b.glyph_infos().iter().map(|i| i.cluster()).collect();
+    /// assert_eq!(clusters, [0, 1, 3]);
+    /// ```
+    pub fn add_utf16(&mut self, text: &[u16], start_at: usize, length: Option<usize>) {
+        let (text_length, item_offset, item_length) = item_args(text.len(), start_at, length);
+        unsafe {
+            sys::hb_buffer_add_utf16(
+                self.raw,
+                text.as_ptr(),
+                text_length,
+                item_offset,
+                item_length,
+            )
+        };
+    }
+
+    /// Add UTF-32 encoded text to the buffer.
+    ///
+    /// `start_at` and `length` select the item to add as for
+    /// [`add_str`](#method.add_str), and clusters are indices in `text`.
+    /// Values that are not Unicode scalar values are replaced by the
+    /// [`replacement_codepoint`](#method.replacement_codepoint).
+    ///
+    /// Panics like [`add_utf16`](#method.add_utf16).
+    pub fn add_utf32(&mut self, text: &[u32], start_at: usize, length: Option<usize>) {
+        let (text_length, item_offset, item_length) = item_args(text.len(), start_at, length);
+        unsafe {
+            sys::hb_buffer_add_utf32(
+                self.raw,
+                text.as_ptr(),
+                text_length,
+                item_offset,
+                item_length,
+            )
+        };
+    }
+
+    /// Add Latin-1 (ISO-8859-1) encoded text to the buffer.
+    ///
+    /// `start_at` and `length` select the item to add as for
+    /// [`add_str`](#method.add_str), and clusters are byte indices in
+    /// `text`.
+    ///
+    /// Panics like [`add_utf16`](#method.add_utf16).
+    ///
+    /// ```
+    /// # use harfbuzz::Buffer;
+    /// let mut b = Buffer::new();
+    /// b.add_latin1(b"caf\xe9", 0, None);
+    /// assert_eq!(b.glyph_infos()[3].codepoint(), 'é' as u32);
+    /// ```
+    pub fn add_latin1(&mut self, text: &[u8], start_at: usize, length: Option<usize>) {
+        let (text_length, item_offset, item_length) = item_args(text.len(), start_at, length);
+        unsafe {
+            sys::hb_buffer_add_latin1(
+                self.raw,
+                text.as_ptr(),
+                text_length,
+                item_offset,
+                item_length,
+            )
+        };
+    }
+
+    /// Add codepoints to the buffer.
+    ///
+    /// Unlike [`add_utf32`](#method.add_utf32), the values are added
+    /// verbatim without being checked for validity. `start_at` and
+    /// `length` select the item to add as for [`add_str`](#method.add_str),
+    /// and clusters are indices in `text`.
+    ///
+    /// Panics like [`add_utf16`](#method.add_utf16).
+    pub fn add_codepoints(&mut self, text: &[Codepoint], start_at: usize, length: Option<usize>) {
+        let (text_length, item_offset, item_length) = item_args(text.len(), start_at, length);
+        unsafe {
+            sys::hb_buffer_add_codepoints(
+                self.raw,
+                text.as_ptr(),
+                text_length,
+                item_offset,
+                item_length,
             )
         };
     }
 
+    /// Add a single `codepoint` with the given `cluster` to the buffer.
+    ///
+    /// No shaping context is recorded, so this is mostly useful to
+    /// build buffers by hand. An empty buffer is set to hold Unicode
+    /// characters.
+    ///
+    /// ```
+    /// # use harfbuzz::{Buffer, BufferContentType};
+    /// let mut b = Buffer::new();
+    /// b.add('a' as u32, 0);
+    /// b.add('b' as u32, 7);
+    /// assert_eq!(b.len(), 2);
+    /// assert_eq!(b.glyph_infos()[1].cluster(), 7);
+    /// assert_eq!(b.content_type(), BufferContentType::Unicode);
+    /// ```
+    pub fn add(&mut self, codepoint: Codepoint, cluster: u32) {
+        unsafe {
+            // Unlike the other functions adding text, `hb_buffer_add` does
+            // not mark the buffer as holding Unicode, which shaping needs.
+            if sys::hb_buffer_get_content_type(self.raw) == sys::HB_BUFFER_CONTENT_TYPE_INVALID {
+                sys::hb_buffer_set_content_type(self.raw, sys::HB_BUFFER_CONTENT_TYPE_UNICODE);
+            }
+            sys::hb_buffer_add(self.raw, codepoint, cluster)
+        };
+    }
+
+    /// Create a buffer of glyphs from their serialized form, as produced by
+    /// [`serialize`](#method.serialize).
+    ///
+    /// The `font` is used to look up glyphs given by name; without one,
+    /// only glyph IDs can be parsed. `[]` gives an empty buffer, and input
+    /// that ends before the closing bracket is an error. On failure, the
+    /// error holds the byte offset in `text` at which parsing stopped.
+    ///
+    /// ```
+    /// # use harfbuzz::{Buffer, Error, SerializeFlags, SerializeFormat};
+    /// let b = Buffer::deserialize_glyphs("[5=0+500|7=1@10,0+0]", None, SerializeFormat::Text)
+    ///     .unwrap();
+    /// assert_eq!(b.len(), 2);
+    /// assert_eq!(b.glyph_infos()[1].codepoint(), 7);
+    /// assert_eq!(b.glyph_positions()[1].x_offset(), 10);
+    /// assert_eq!(
+    ///     b.serialize(None, SerializeFormat::Text, SerializeFlags::NO_GLYPH_NAMES),
+    ///     "[5=0+500|7=1@10,0+0]"
+    /// );
+    ///
+    /// let err = Buffer::deserialize_glyphs("[5=0+500|x]", None, SerializeFormat::Text);
+    /// assert!(matches!(err, Err(Error::Deserialize(8))));
+    /// ```
+    pub fn deserialize_glyphs(
+        text: &str,
+        font: Option<&Font>,
+        format: SerializeFormat,
+    ) -> Result<Buffer, Error> {
+        let font = font.map_or(std::ptr::null_mut(), |font| font.as_ptr() as *mut _);
+        // HarfBuzz searches the text for delimiters with `strchr`, so it
+        // must be nul-terminated.
+        let text = CString::new(text).map_err(|e| Error::Deserialize(e.nul_position()))?;
+        let b = Buffer::new();
+        let mut end = text.as_ptr();
+        unsafe {
+            sys::hb_buffer_deserialize_glyphs(
+                b.raw,
+                text.as_ptr(),
+                text.as_bytes().len() as std::os::raw::c_int,
+                &mut end,
+                font,
+                format.into(),
+            );
+        }
+        // Glyph names are looked up in the tables of the font.
+        crate::resume_callback_panic();
+        deserialize_result(b, &text, end, (b'[', b']'))
+    }
+
+    /// Create a buffer of Unicode characters from their serialized form, as
+    /// produced by [`serialize`](#method.serialize).
+    ///
+    /// `<>`, or `[]` for JSON, gives an empty buffer, and input that ends
+    /// before the closing bracket is an error. On failure, the error holds
+    /// the byte offset in `text` at which parsing stopped.
+    ///
+    /// ```
+    /// # use harfbuzz::{Buffer, SerializeFormat};
+    /// let b = Buffer::deserialize_unicode("<U+0061=0|U+0062=1>", SerializeFormat::Text).unwrap();
+    /// assert_eq!(b.glyph_infos()[1].codepoint(), 'b' as u32);
+    /// ```
+    pub fn deserialize_unicode(text: &str, format: SerializeFormat) -> Result<Buffer, Error> {
+        let text = CString::new(text).map_err(|e| Error::Deserialize(e.nul_position()))?;
+        let b = Buffer::new();
+        let mut end = text.as_ptr();
+        unsafe {
+            sys::hb_buffer_deserialize_unicode(
+                b.raw,
+                text.as_ptr(),
+                text.as_bytes().len() as std::os::raw::c_int,
+                &mut end,
+                format.into(),
+            );
+        }
+        let brackets = match format {
+            SerializeFormat::Text => (b'<', b'>'),
+            _ => (b'[', b']'),
+        };
+        deserialize_result(b, &text, end, brackets)
+    }
+
     /// Append part of the contents of another buffer to this one.
     ///
     /// ```
@@ -244,20 +544,38 @@ impl Buffer {
         self.len() == 0
     }
 
+    /// Returns whether the buffer holds Unicode characters or shaped glyphs.
+    ///
+    /// ```
+    /// # use harfbuzz::{shape, Blob, Buffer, BufferContentType, Face, Font};
+    /// # let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// # let font = Font::new(&mut face);
+    /// let mut b = Buffer::new();
+    /// assert_eq!(b.content_type(), BufferContentType::Invalid);
+    /// b.add_str("Hello", 0, None);
+    /// assert_eq!(b.content_type(), BufferContentType::Unicode);
+    /// b.guess_segment_properties();
+    /// shape(&font, &mut b, &[]);
+    /// assert_eq!(b.content_type(), BufferContentType::Glyphs);
+    /// ```
+    pub fn content_type(&self) -> BufferContentType {
+        unsafe { sys::hb_buffer_get_content_type(self.raw) }.into()
+    }
+
     /// Sets unset buffer segment properties based on buffer Unicode
     /// contents.
     ///
     /// If buffer is not empty, it must have content type
     /// `HB_BUFFER_CONTENT_TYPE_UNICODE`.
     ///
-    /// If buffer script is not set (ie. is `HB_SCRIPT_INVALID`), it will
+    /// If buffer script is not set (ie. is `Script::INVALID`), it will
     /// be set to the Unicode script of the first character in the buffer
-    /// that has a script other than `HB_SCRIPT_COMMON`,
-    /// `HB_SCRIPT_INHERITED`, and `HB_SCRIPT_UNKNOWN`.
+    /// that has a script other than `Script::COMMON`,
+    /// `Script::INHERITED`, and `Script::UNKNOWN`.
     ///
     /// Next, if buffer direction is not set (ie. is `Direction::Invalid`),
     /// it will be set to the natural horizontal direction of the buffer
-    /// script as returned by `hb_script_get_horizontal_direction()`.
+    /// script as returned by [`Script::horizontal_direction`].
     ///
     /// Finally, if buffer language is not set (ie. is `HB_LANGUAGE_INVALID`),
     /// it will be set to the process's default language as returned by
@@ -265,11 +583,11 @@ impl Buffer {
     /// taking buffer script into consideration when choosing a language.
     ///
     /// ```
-    /// # use harfbuzz::{Buffer, Direction, sys};
+    /// # use harfbuzz::{Buffer, Direction, Script};
     /// let mut b = Buffer::with("Hello, world!");
     /// b.guess_segment_properties();
     /// assert_eq!(b.get_direction(), Direction::LTR);
-    /// assert_eq!(b.get_script(), sys::HB_SCRIPT_LATIN);
+    /// assert_eq!(b.get_script(), Script::LATIN);
     /// ```
     ///
     /// See also:
@@ -321,8 +639,8 @@ impl Buffer {
     ///
     /// * [`get_script`](#method.get_script)
     /// * [`guess_segment_properties`](#method.guess_segment_properties)
-    pub fn set_script(&mut self, script: sys::hb_script_t) {
-        unsafe { sys::hb_buffer_set_script(self.raw, script) };
+    pub fn set_script(&mut self, script: Script) {
+        unsafe { sys::hb_buffer_set_script(self.raw, script.into()) };
     }
 
     /// Get the script for the buffer.
@@ -330,8 +648,8 @@ impl Buffer {
     /// See also:
     ///
     /// * [`set_script`](#method.set_script)
-    pub fn get_script(&self) -> sys::hb_script_t {
-        unsafe { sys::hb_buffer_get_script(self.raw) }
+    pub fn get_script(&self) -> Script {
+        unsafe { sys::hb_buffer_get_script(self.raw) }.into()
     }
 
     /// Sets the language of buffer to *language*.
@@ -359,38 +677,409 @@ impl Buffer {
         unsafe { Language::from_raw(sys::hb_buffer_get_language(self.raw)) }
     }
 
+    /// Sets the direction, script and language of the buffer at once.
+    ///
+    /// See also:
+    ///
+    /// * [`segment_properties`](#method.segment_properties)
+    /// * [`guess_segment_properties`](#method.guess_segment_properties)
+    pub fn set_segment_properties(&mut self, props: &SegmentProperties) {
+        let props = sys::hb_segment_properties_t::from(*props);
+        unsafe { sys::hb_buffer_set_segment_properties(self.raw, &props) };
+    }
+
+    /// Get the direction, script and language of the buffer.
+    ///
+    /// ```
+    /// # use harfbuzz::{Buffer, Direction, Script};
+    /// let mut b = Buffer::with("Γειά σου");
+    /// b.guess_segment_properties();
+    /// let props = b.segment_properties();
+    /// assert_eq!(props.direction(), Direction::LTR);
+    /// assert_eq!(props.script(), Script::GREEK);
+    /// ```
+    ///
+    /// See also:
+    ///
+    /// * [`set_segment_properties`](#method.set_segment_properties)
+    pub fn segment_properties(&self) -> SegmentProperties {
+        let mut props = SegmentProperties::default().into();
+        unsafe { sys::hb_buffer_get_segment_properties(self.raw, &mut props) };
+        props.into()
+    }
+
     /// Get glyph informations for the buffer.
-    pub fn glyph_infos(&self) -> Vec<GlyphInfo> {
-        let mut infos: Vec<GlyphInfo> = Vec::new();
+    ///
+    /// Before shaping these are the Unicode characters of the buffer,
+    /// after shaping they are the glyphs. The slice borrows the buffer's
+    /// own storage, so nothing is copied.
+    pub fn glyph_infos(&self) -> &[GlyphInfo] {
         let mut count = 0;
         unsafe {
             let ptr = sys::hb_buffer_get_glyph_infos(self.raw, &mut count);
-            infos.reserve(count as usize);
-            std::ptr::copy_nonoverlapping(
-                ptr,
-                infos.as_mut_ptr() as *mut sys::hb_glyph_info_t,
-                count as usize,
-            );
-            infos.set_len(count as usize);
+            if ptr.is_null() || count == 0 {
+                return &[];
+            }
+            std::slice::from_raw_parts(ptr as *const GlyphInfo, count as usize)
         }
-        infos
     }
 
     /// Get glyph positions for the buffer.
-    pub fn glyph_positions(&self) -> Vec<GlyphPosition> {
-        let mut positions: Vec<GlyphPosition> = Vec::new();
+    ///
+    /// The slice borrows the buffer's own storage, so nothing is copied.
+    pub fn glyph_positions(&self) -> &[GlyphPosition] {
         let mut count = 0;
         unsafe {
             let ptr = sys::hb_buffer_get_glyph_positions(self.raw, &mut count);
-            positions.reserve(count as usize);
-            std::ptr::copy_nonoverlapping(
-                ptr,
-                positions.as_mut_ptr() as *mut sys::hb_glyph_position_t,
-                count as usize,
-            );
-            positions.set_len(count as usize);
+            if ptr.is_null() || count == 0 {
+                return &[];
+            }
+            std::slice::from_raw_parts(ptr as *const GlyphPosition, count as usize)
+        }
+    }
+
+    /// Get mutable glyph positions for the buffer.
+    ///
+    /// This allows adjusting the positions of a shaped buffer in place,
+    /// e.g. to apply letter-spacing:
+    ///
+    /// ```
+    /// # use harfbuzz::{shape, Blob, Buffer, Face, Font};
+    /// # let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// # let font = Font::new(&mut face);
+    /// let mut b = Buffer::with("abc");
+    /// b.guess_segment_properties();
+    /// shape(&font, &mut b, &[]);
+    /// let advance = b.glyph_positions()[0].x_advance();
+    ///
+    /// for pos in b.glyph_positions_mut() {
+    ///     pos.set_x_advance(pos.x_advance() + 100);
+    /// }
+    /// assert_eq!(b.glyph_positions()[0].x_advance(), advance + 100);
+    /// ```
+    pub fn glyph_positions_mut(&mut self) -> &mut [GlyphPosition] {
+        let mut count = 0;
+        unsafe {
+            let ptr = sys::hb_buffer_get_glyph_positions(self.raw, &mut count);
+            if ptr.is_null() || count == 0 {
+                return &mut [];
+            }
+            std::slice::from_raw_parts_mut(ptr as *mut GlyphPosition, count as usize)
         }
-        positions
+    }
+
+    /// Returns the glyph indices at which a shaped buffer can be split
+    /// without reshaping either side.
+    ///
+    /// Each returned index `i` is a cluster boundary between the glyphs
+    /// `i - 1` and `i`, so the glyphs before and after it can be reused
+    /// as they are. The start and end of the buffer are not included.
+    ///
+    /// ```
+    /// # use harfbuzz::{shape, Blob, Buffer, Face, Font};
+    /// # let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// # let font = Font::new(&mut face);
+    /// let mut b = Buffer::with("abc");
+    /// b.guess_segment_properties();
+    /// shape(&font, &mut b, &[]);
+    /// assert_eq!(b.safe_to_break_points().collect::<Vec<_>>(), [1, 2]);
+    /// ```
+    pub fn safe_to_break_points(&self) -> impl Iterator<Item = usize> + '_ {
+        let infos = self.glyph_infos();
+        // The flag is on the glyphs of the cluster that logically starts at
+        // the boundary, which is the visually later one in backward text.
+        let backward = self.get_direction().is_backward();
+        (1..infos.len()).filter(move |&i| {
+            let (before, after) = (&infos[i - 1], &infos[i]);
+            let start = if backward { before } else { after };
+            before.cluster() != after.cluster() && !start.unsafe_to_break()
+        })
+    }
+
+    /// Compare the buffer with a `reference` buffer, e.g. the stored result
+    /// of shaping the same text with an earlier HarfBuzz.
+    ///
+    /// Positions that differ by no more than `position_fuzz` are
+    /// considered equal. If both buffers are non-empty and hold different
+    /// kinds of content, only [`BufferDiffFlags::CONTENT_TYPE_MISMATCH`]
+    /// is reported.
+    ///
+    /// If `dotted_circle_glyph` is given, `reference` is also checked for
+    /// that glyph and for the `.notdef` glyph, which are reported with
+    /// [`BufferDiffFlags::DOTTED_CIRCLE_PRESENT`] and
+    /// [`BufferDiffFlags::NOTDEF_PRESENT`]. Without it, neither is
+    /// reported.
+    ///
+    /// ```
+    /// # use harfbuzz::{Buffer, BufferDiffFlags, SerializeFormat};
+    /// let a = Buffer::deserialize_glyphs("[5=0+500|7=1+300]", None, SerializeFormat::Text)
+    ///     .unwrap();
+    /// let b = Buffer::deserialize_glyphs("[5=0+500|8=1+302]", None, SerializeFormat::Text)
+    ///     .unwrap();
+    ///
+    /// let diff = a.diff(&b, None, 0);
+    /// assert_eq!(
+    ///     diff,
+    ///     BufferDiffFlags::CODEPOINT_MISMATCH | BufferDiffFlags::POSITION_MISMATCH
+    /// );
+    /// assert_eq!(a.diff(&b, None, 2), BufferDiffFlags::CODEPOINT_MISMATCH);
+    /// assert!(a.diff(&a, None, 0).is_equal());
+    /// ```
+    pub fn diff(
+        &self,
+        reference: &Buffer,
+        dotted_circle_glyph: Option<Codepoint>,
+        position_fuzz: u32,
+    ) -> BufferDiffFlags {
+        let flags = unsafe {
+            sys::hb_buffer_diff(
+                self.raw,
+                reference.raw,
+                dotted_circle_glyph.unwrap_or(Codepoint::MAX),
+                position_fuzz,
+            )
+        };
+        BufferDiffFlags::from_bits_truncate(flags)
+    }
+
+    /// Compare the buffer with a `reference` buffer glyph by glyph, and
+    /// return the indices of the glyphs that differ together with how.
+    ///
+    /// Glyphs that only one of the buffers has are reported with
+    /// [`BufferDiffFlags::LENGTH_MISMATCH`]. If both buffers are non-empty
+    /// and hold different kinds of content, like [`diff`](#method.diff)
+    /// the glyphs are not compared, and the only entry is
+    /// `(0, BufferDiffFlags::CONTENT_TYPE_MISMATCH)`.
+    ///
+    /// ```
+    /// # use harfbuzz::{Buffer, BufferDiffFlags, SerializeFormat};
+    /// let a = Buffer::deserialize_glyphs("[5=0+500|7=1+300]", None, SerializeFormat::Text)
+    ///     .unwrap();
+    /// let b = Buffer::deserialize_glyphs("[5=0+500|7=2+300|9=3+0]", None, SerializeFormat::Text)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     a.diff_glyphs(&b, 0),
+    ///     [
+    ///         (1, BufferDiffFlags::CLUSTER_MISMATCH),
+    ///         (2, BufferDiffFlags::LENGTH_MISMATCH),
+    ///     ]
+    /// );
+    /// ```
+    pub fn diff_glyphs(
+        &self,
+        reference: &Buffer,
+        position_fuzz: u32,
+    ) -> Vec<(usize, BufferDiffFlags)> {
+        let (infos, ref_infos) = (self.glyph_infos(), reference.glyph_infos());
+        if self.content_type() != reference.content_type()
+            && !infos.is_empty()
+            && !ref_infos.is_empty()
+        {
+            return vec![(0, BufferDiffFlags::CONTENT_TYPE_MISMATCH)];
+        }
+        let glyphs = self.content_type() == BufferContentType::Glyphs
+            && reference.content_type() == BufferContentType::Glyphs;
+        let (positions, ref_positions) = if glyphs {
+            (self.glyph_positions(), reference.glyph_positions())
+        } else {
+            (&[][..], &[][..])
+        };
+
+        let mut diffs = Vec::new();
+        for i in 0..infos.len().max(ref_infos.len()) {
+            let (info, ref_info) = match (infos.get(i), ref_infos.get(i)) {
+                (Some(info), Some(ref_info)) => (info, ref_info),
+                _ => {
+                    diffs.push((i, BufferDiffFlags::LENGTH_MISMATCH));
+                    continue;
+                }
+            };
+            let mut flags = BufferDiffFlags::EQUAL;
+            if info.codepoint() != ref_info.codepoint() {
+                flags |= BufferDiffFlags::CODEPOINT_MISMATCH;
+            }
+            if info.cluster() != ref_info.cluster() {
+                flags |= BufferDiffFlags::CLUSTER_MISMATCH;
+            }
+            if info.flags() != ref_info.flags() {
+                flags |= BufferDiffFlags::GLYPH_FLAGS_MISMATCH;
+            }
+            if let (Some(pos), Some(ref_pos)) = (positions.get(i), ref_positions.get(i)) {
+                let fuzzy_ne = |a: i32, b: i32| a.abs_diff(b) > position_fuzz;
+                if fuzzy_ne(pos.x_advance(), ref_pos.x_advance())
+                    || fuzzy_ne(pos.y_advance(), ref_pos.y_advance())
+                    || fuzzy_ne(pos.x_offset(), ref_pos.x_offset())
+                    || fuzzy_ne(pos.y_offset(), ref_pos.y_offset())
+                {
+                    flags |= BufferDiffFlags::POSITION_MISMATCH;
+                }
+            }
+            if !flags.is_equal() {
+                diffs.push((i, flags));
+            }
+        }
+        diffs
+    }
+
+    /// Set a function to be called with messages from HarfBuzz while
+    /// shaping the buffer.
+    ///
+    /// The function receives the buffer in its current state, the font it
+    /// is shaped with and a message such as `start lookup 3`. Returning
+    /// `false` stops the step that sent the message, e.g. skips that
+    /// lookup. Any previously set function is dropped.
+    ///
+    /// A panic in the function stops shaping, and is resumed once control
+    /// returns from [`shape`](crate::shape).
+    ///
+    /// ```
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// # use harfbuzz::{shape, Blob, Buffer, Face, Font};
+    /// # let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// # let font = Font::new(&mut face);
+    /// let messages = Rc::new(RefCell::new(Vec::new()));
+    /// let sink = messages.clone();
+    ///
+    /// let mut b = Buffer::with("abc");
+    /// b.guess_segment_properties();
+    /// b.set_message_func(move |_, _, message| {
+    ///     sink.borrow_mut().push(message.to_owned());
+    ///     true
+    /// });
+    /// shape(&font, &mut b, &[]);
+    /// assert!(messages.borrow().iter().any(|m| m.starts_with("start")));
+    /// ```
+    pub fn set_message_func<F>(&mut self, func: F)
+    where
+        F: FnMut(&Buffer, &Font, &str) -> bool + 'static,
+    {
+        unsafe extern "C" fn message_func<F>(
+            buffer: *mut sys::hb_buffer_t,
+            font: *mut sys::hb_font_t,
+            message: *const std::os::raw::c_char,
+            user_data: *mut std::os::raw::c_void,
+        ) -> sys::hb_bool_t
+        where
+            F: FnMut(&Buffer, &Font, &str) -> bool + 'static,
+        {
+            if crate::callback_panicked() {
+                return 0;
+            }
+            let func = &mut *(user_data as *mut F);
+            // The buffer and font are only borrowed for the duration of
+            // the call, so they must not be destroyed when dropped.
+            let buffer = ManuallyDrop::new(Buffer::from_raw(buffer));
+            let font = ManuallyDrop::new(FontMut::from_raw(font));
+            let message = CStr::from_ptr(message).to_string_lossy();
+            match panic::catch_unwind(AssertUnwindSafe(|| func(&buffer, &font, &message))) {
+                Ok(true) => 1,
+                Ok(false) => 0,
+                Err(payload) => {
+                    crate::store_callback_panic(payload);
+                    0
+                }
+            }
+        }
+
+        unsafe extern "C" fn destroy<F>(user_data: *mut std::os::raw::c_void) {
+            drop(Box::from_raw(user_data as *mut F))
+        }
+
+        let func = Box::into_raw(Box::new(func));
+        unsafe {
+            sys::hb_buffer_set_message_func(
+                self.raw,
+                Some(message_func::<F>),
+                func as *mut std::os::raw::c_void,
+                Some(destroy::<F>),
+            )
+        };
+    }
+
+    /// Remove the function set with
+    /// [`set_message_func`](#method.set_message_func), dropping it.
+    pub fn clear_message_func(&mut self) {
+        unsafe { sys::hb_buffer_set_message_func(self.raw, None, std::ptr::null_mut(), None) };
+    }
+
+    /// Serialize the contents of the buffer to a string.
+    ///
+    /// Unicode buffers are serialized as their codepoints and glyph buffers
+    /// as their glyphs, in the formats used by the `hb-shape` utility.
+    /// The `font` is used to look up glyph names and extents; without one,
+    /// glyphs are serialized by ID.
+    ///
+    /// An empty buffer serializes to an empty string, rather than the
+    /// `!!` or `[]` that HarfBuzz writes for no items.
+    ///
+    /// ```
+    /// # use harfbuzz::{shape, Blob, Buffer, Face, Font, SerializeFlags, SerializeFormat};
+    /// # let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// # let font = Font::new(&mut face);
+    /// let mut b = Buffer::with("ab");
+    /// assert_eq!(
+    ///     b.serialize(None, SerializeFormat::Text, SerializeFlags::default()),
+    ///     "<U+0061=0|U+0062=1>"
+    /// );
+    ///
+    /// b.guess_segment_properties();
+    /// shape(&font, &mut b, &[]);
+    /// assert_eq!(
+    ///     b.serialize(Some(&font), SerializeFormat::Text, SerializeFlags::NO_POSITIONS),
+    ///     "[gid0=0|gid0=1]"
+    /// );
+    /// ```
+    pub fn serialize(
+        &self,
+        font: Option<&Font>,
+        format: SerializeFormat,
+        flags: SerializeFlags,
+    ) -> String {
+        // HarfBuzz can not serialize a buffer that holds neither text nor
+        // glyphs, which is only possible when it is empty.
+        if self.content_type() == BufferContentType::Invalid {
+            return String::new();
+        }
+        let font = font.map_or(std::ptr::null_mut(), |font| font.as_ptr() as *mut _);
+        let len = self.len() as std::os::raw::c_uint;
+        let mut out = Vec::new();
+        let mut chunk = vec![0u8; 4096];
+        let mut start = 0;
+        while start < len {
+            let mut consumed = 0;
+            let serialized = unsafe {
+                sys::hb_buffer_serialize(
+                    self.raw,
+                    start,
+                    len,
+                    chunk.as_mut_ptr() as *mut std::os::raw::c_char,
+                    chunk.len() as std::os::raw::c_uint,
+                    &mut consumed,
+                    font,
+                    format.into(),
+                    flags.bits(),
+                )
+            };
+            // Glyph names and extents are looked up in the tables of the
+            // font.
+            crate::resume_callback_panic();
+            if serialized == 0 {
+                // With a valid content type and format, this only happens
+                // when not even a single item fit into the chunk. No item
+                // comes close to a megabyte, so stop rather than grow
+                // without bound should HarfBuzz fail for another reason.
+                if chunk.len() >= 1 << 20 {
+                    break;
+                }
+                chunk.resize(chunk.len() * 2, 0);
+                continue;
+            }
+            out.extend_from_slice(&chunk[..consumed as usize]);
+            start += serialized;
+        }
+        String::from_utf8_lossy(&out).into_owned()
     }
 
     // /// Whether buffer has glyph position data
@@ -415,6 +1104,24 @@ impl Buffer {
         unsafe { sys::hb_buffer_set_flags(self.raw, flags.bits()) };
     }
 
+    /// Sets how cluster values are merged during shaping.
+    ///
+    /// ```
+    /// # use harfbuzz::{Buffer, ClusterLevel};
+    /// let mut b = Buffer::new();
+    /// assert_eq!(b.cluster_level(), ClusterLevel::MonotoneGraphemes);
+    /// b.set_cluster_level(ClusterLevel::Characters);
+    /// assert_eq!(b.cluster_level(), ClusterLevel::Characters);
+    /// ```
+    pub fn set_cluster_level(&mut self, cluster_level: ClusterLevel) {
+        unsafe { sys::hb_buffer_set_cluster_level(self.raw, cluster_level.into()) };
+    }
+
+    /// Get how cluster values are merged during shaping.
+    pub fn cluster_level(&self) -> ClusterLevel {
+        unsafe { sys::hb_buffer_get_cluster_level(self.raw) }.into()
+    }
+
     /* Since: 3.1.0
     pub fn not_found_glyph(&self) -> Codepoint {
         unsafe { sys::hb_buffer_get_not_found_glyph(self.raw) }
@@ -475,6 +1182,426 @@ impl Drop for Buffer {
     }
 }
 
+/// The kind of data held by a [`Buffer`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BufferContentType {
+    /// Initial value for a new buffer, or one whose contents were cleared.
+    Invalid,
+    /// The buffer contains input characters, before shaping.
+    Unicode,
+    /// The buffer contains output glyphs, after shaping.
+    Glyphs,
+}
+
+impl From<sys::hb_buffer_content_type_t> for BufferContentType {
+    fn from(s: sys::hb_buffer_content_type_t) -> Self {
+        match s {
+            sys::HB_BUFFER_CONTENT_TYPE_UNICODE => BufferContentType::Unicode,
+            sys::HB_BUFFER_CONTENT_TYPE_GLYPHS => BufferContentType::Glyphs,
+            _ => BufferContentType::Invalid,
+        }
+    }
+}
+
+impl From<BufferContentType> for sys::hb_buffer_content_type_t {
+    fn from(s: BufferContentType) -> Self {
+        match s {
+            BufferContentType::Invalid => sys::HB_BUFFER_CONTENT_TYPE_INVALID,
+            BufferContentType::Unicode => sys::HB_BUFFER_CONTENT_TYPE_UNICODE,
+            BufferContentType::Glyphs => sys::HB_BUFFER_CONTENT_TYPE_GLYPHS,
+        }
+    }
+}
+
+/// A range of a text that is to be shaped, together with the text around it.
+///
+/// Shaping often depends on the characters next to the ones being shaped:
+/// whether an Arabic letter takes its initial, medial or final form is
+/// decided by its neighbours, even when those are in another run of the
+/// paragraph. A `TextItem` keeps the whole text so that HarfBuzz can use
+/// the [`pre_context`](#method.pre_context) and
+/// [`post_context`](#method.post_context) of the [`item`](#method.item)
+/// while only the item itself gets shaped. HarfBuzz looks at up to five
+/// characters of context on either side.
+///
+/// ```
+/// # use harfbuzz::TextItem;
+/// let item = TextItem::new("Hello, world!", 7..12).unwrap();
+/// assert_eq!(item.pre_context(), "Hello, ");
+/// assert_eq!(item.item(), "world");
+/// assert_eq!(item.post_context(), "!");
+///
+/// // "é" takes two bytes, so this range would split it.
+/// assert!(TextItem::new("café", 0..4).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextItem<'a> {
+    text: &'a str,
+    range: Range<usize>,
+}
+
+impl<'a> TextItem<'a> {
+    /// Create an item for the bytes `range` of `text`.
+    ///
+    /// Returns [`Error::InvalidRange`] if the range is out of bounds or
+    /// does not start and end on character boundaries.
+    pub fn new(text: &'a str, range: Range<usize>) -> Result<Self, Error> {
+        if range.start > range.end || text.get(range.clone()).is_none() {
+            return Err(Error::InvalidRange(range));
+        }
+        Ok(TextItem { text, range })
+    }
+
+    /// Create an item covering all of `text`, with no context.
+    pub fn whole(text: &'a str) -> Self {
+        TextItem {
+            text,
+            range: 0..text.len(),
+        }
+    }
+
+    /// The whole text, including context.
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// The byte range of the item in [`text`](#method.text).
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The text before the item.
+    pub fn pre_context(&self) -> &'a str {
+        &self.text[..self.range.start]
+    }
+
+    /// The text to be shaped.
+    pub fn item(&self) -> &'a str {
+        &self.text[self.range.clone()]
+    }
+
+    /// The text after the item.
+    pub fn post_context(&self) -> &'a str {
+        &self.text[self.range.end..]
+    }
+}
+
+/// A [`Buffer`] that holds Unicode text waiting to be shaped.
+///
+/// Shaping consumes the `UnicodeBuffer` and returns a [`GlyphBuffer`],
+/// so a buffer can neither be shaped twice nor have its positions read
+/// before it was shaped. Clearing the `GlyphBuffer` gives back an empty
+/// `UnicodeBuffer` that reuses the allocations of the previous run:
+///
+/// ```
+/// # use harfbuzz::{Blob, Face, Font, UnicodeBuffer};
+/// # let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+/// # let font = Font::new(&mut face);
+/// let mut buffer = UnicodeBuffer::new();
+/// for word in &["Hello", "World"] {
+///     buffer.add_str(word);
+///     buffer.guess_segment_properties();
+///     let glyphs = buffer.shape(&font, &[]);
+///     assert_eq!(glyphs.glyph_positions().len(), 5);
+///     buffer = glyphs.clear();
+///     assert!(buffer.is_empty());
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct UnicodeBuffer(Buffer);
+
+impl UnicodeBuffer {
+    /// Create a new, empty buffer.
+    pub fn new() -> Self {
+        UnicodeBuffer::default()
+    }
+
+    /// Add UTF-8 encoded text to the buffer.
+    pub fn add_str(&mut self, text: &str) {
+        self.0.add_str(text, 0, None);
+    }
+
+    /// Add an item of a larger text to the buffer, using the rest of the
+    /// text as context.
+    pub fn add_item(&mut self, item: &TextItem) {
+        self.0.add_item(item);
+    }
+
+    /// Add UTF-16 encoded text to the buffer.
+    ///
+    /// See [`Buffer::add_utf16`], including when this panics.
+    ///
+    /// ```
+    /// # use harfbuzz::UnicodeBuffer;
+    /// let text: Vec<u16> = "one two".encode_utf16().collect();
+    /// let mut b = UnicodeBuffer::new();
+    /// b.add_utf16(&text, 4, Some(3));
+    /// assert_eq!(b.len(), 3);
+    /// ```
+    pub fn add_utf16(&mut self, text: &[u16], start_at: usize, length: Option<usize>) {
+        self.0.add_utf16(text, start_at, length);
+    }
+
+    /// Add UTF-32 encoded text to the buffer.
+    ///
+    /// See [`Buffer::add_utf32`], including when this panics.
+    pub fn add_utf32(&mut self, text: &[u32], start_at: usize, length: Option<usize>) {
+        self.0.add_utf32(text, start_at, length);
+    }
+
+    /// Add Latin-1 (ISO-8859-1) encoded text to the buffer.
+    ///
+    /// See [`Buffer::add_latin1`], including when this panics.
+    pub fn add_latin1(&mut self, text: &[u8], start_at: usize, length: Option<usize>) {
+        self.0.add_latin1(text, start_at, length);
+    }
+
+    /// Add codepoints to the buffer without checking them.
+    ///
+    /// See [`Buffer::add_codepoints`], including when this panics.
+    pub fn add_codepoints(&mut self, text: &[Codepoint], start_at: usize, length: Option<usize>) {
+        self.0.add_codepoints(text, start_at, length);
+    }
+
+    /// Add a single `codepoint` with the given `cluster` to the buffer.
+    pub fn add(&mut self, codepoint: Codepoint, cluster: u32) {
+        self.0.add(codepoint, cluster);
+    }
+
+    /// Returns the number of characters in the buffer.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the buffer contains no characters.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Sets unset segment properties based on the buffer contents.
+    ///
+    /// See [`Buffer::guess_segment_properties`].
+    pub fn guess_segment_properties(&mut self) {
+        self.0.guess_segment_properties();
+    }
+
+    /// Set the text flow direction of the buffer.
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.0.set_direction(direction);
+    }
+
+    /// Sets the script of the buffer.
+    pub fn set_script(&mut self, script: Script) {
+        self.0.set_script(script);
+    }
+
+    /// Sets the language of the buffer.
+    pub fn set_language(&mut self, language: Language) {
+        self.0.set_language(language);
+    }
+
+    /// Sets the direction, script and language of the buffer at once.
+    pub fn set_segment_properties(&mut self, props: &SegmentProperties) {
+        self.0.set_segment_properties(props);
+    }
+
+    /// Get the direction, script and language of the buffer.
+    pub fn segment_properties(&self) -> SegmentProperties {
+        self.0.segment_properties()
+    }
+
+    /// Sets buffer flags.
+    pub fn set_flags(&mut self, flags: BufferFlags) {
+        self.0.set_flags(flags);
+    }
+
+    /// Sets how cluster values are merged during shaping.
+    pub fn set_cluster_level(&mut self, cluster_level: ClusterLevel) {
+        self.0.set_cluster_level(cluster_level);
+    }
+
+    /// Shape the text of the buffer using `font`, turning it into positioned
+    /// glyphs.
+    ///
+    /// See [`shape`](crate::shape) for how `features` are applied.
+    pub fn shape(mut self, font: &Font, features: &[Feature]) -> GlyphBuffer {
+        crate::shape(font, &mut self.0, features);
+        GlyphBuffer(self.0)
+    }
+
+    /// Gives up the type state and returns the underlying `Buffer`.
+    pub fn into_inner(self) -> Buffer {
+        self.0
+    }
+}
+
+/// Takes a buffer that has not been shaped yet.
+///
+/// Gives the buffer back if it holds glyphs.
+impl TryFrom<Buffer> for UnicodeBuffer {
+    type Error = Buffer;
+
+    fn try_from(buffer: Buffer) -> Result<Self, Self::Error> {
+        match buffer.content_type() {
+            BufferContentType::Glyphs => Err(buffer),
+            _ => Ok(UnicodeBuffer(buffer)),
+        }
+    }
+}
+
+/// A [`Buffer`] that holds the positioned glyphs produced by
+/// [`UnicodeBuffer::shape`].
+#[derive(Debug)]
+pub struct GlyphBuffer(Buffer);
+
+impl GlyphBuffer {
+    /// Returns the number of glyphs in the buffer.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the buffer contains no glyphs.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the glyph informations.
+    pub fn glyph_infos(&self) -> &[GlyphInfo] {
+        self.0.glyph_infos()
+    }
+
+    /// Get the glyph positions.
+    pub fn glyph_positions(&self) -> &[GlyphPosition] {
+        self.0.glyph_positions()
+    }
+
+    /// Get mutable glyph positions.
+    pub fn glyph_positions_mut(&mut self) -> &mut [GlyphPosition] {
+        self.0.glyph_positions_mut()
+    }
+
+    /// Returns the glyph indices at which the buffer can be split without
+    /// reshaping either side.
+    ///
+    /// See [`Buffer::safe_to_break_points`].
+    pub fn safe_to_break_points(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.safe_to_break_points()
+    }
+
+    /// Serialize the glyphs to a string.
+    ///
+    /// See [`Buffer::serialize`].
+    pub fn serialize(
+        &self,
+        font: Option<&Font>,
+        format: SerializeFormat,
+        flags: SerializeFlags,
+    ) -> String {
+        self.0.serialize(font, format, flags)
+    }
+
+    /// Get the direction, script and language the buffer was shaped with.
+    pub fn segment_properties(&self) -> SegmentProperties {
+        self.0.segment_properties()
+    }
+
+    /// Throw away the glyphs and segment properties, and get back an empty
+    /// [`UnicodeBuffer`] that reuses this buffer's allocations.
+    ///
+    /// Flags and replacement settings are kept.
+    pub fn clear(mut self) -> UnicodeBuffer {
+        self.0.clear_contents();
+        UnicodeBuffer(self.0)
+    }
+
+    /// Gives up the type state and returns the underlying `Buffer`.
+    pub fn into_inner(self) -> Buffer {
+        self.0
+    }
+}
+
+/// Takes a buffer that has been shaped.
+///
+/// Gives the buffer back if it does not hold glyphs.
+impl TryFrom<Buffer> for GlyphBuffer {
+    type Error = Buffer;
+
+    fn try_from(buffer: Buffer) -> Result<Self, Self::Error> {
+        match buffer.content_type() {
+            BufferContentType::Glyphs => Ok(GlyphBuffer(buffer)),
+            _ => Err(buffer),
+        }
+    }
+}
+
+/// The `text_length`, `item_offset` and `item_length` arguments of the
+/// `hb_buffer_add_*` functions, for the item of a text of `len` units
+/// starting at `start_at`.
+///
+/// HarfBuzz does not check these, so this panics if the item is out of
+/// bounds or the text is too long to describe.
+fn item_args(
+    len: usize,
+    start_at: usize,
+    length: Option<usize>,
+) -> (
+    std::os::raw::c_int,
+    std::os::raw::c_uint,
+    std::os::raw::c_int,
+) {
+    assert!(
+        len <= i32::MAX as usize,
+        "text of length {} is too long for HarfBuzz",
+        len
+    );
+    let end = length.map_or(Some(len), |length| start_at.checked_add(length));
+    assert!(
+        start_at <= len && end.is_some_and(|end| end <= len),
+        "item at {} of length {:?} is out of bounds for text of length {}",
+        start_at,
+        length,
+        len
+    );
+    (
+        len as std::os::raw::c_int,
+        start_at as std::os::raw::c_uint,
+        length.map_or(-1, |length| length as std::os::raw::c_int),
+    )
+}
+
+/// Check the outcome of `hb_buffer_deserialize_*`.
+///
+/// Those functions are meant for reading a stream of items, and their
+/// return value only tells whether more items could follow, which is also
+/// the case for truncated input. Success is told by whether all input up
+/// to and including the `close` bracket was consumed instead.
+///
+/// HarfBuzz does not parse a pair of brackets with nothing in between,
+/// the serialized form of an empty buffer, so that is accepted here.
+fn deserialize_result(
+    b: Buffer,
+    text: &CString,
+    end: *const std::os::raw::c_char,
+    (open, close): (u8, u8),
+) -> Result<Buffer, Error> {
+    let mut items = text.as_bytes().iter().filter(|c| !c.is_ascii_whitespace());
+    if items.next() == Some(&open) && items.next() == Some(&close) && items.next().is_none() {
+        return Ok(b);
+    }
+    let consumed = end as usize - text.as_ptr() as usize;
+    let (done, rest) = text.as_bytes().split_at(consumed);
+    let closed = done
+        .iter()
+        .rev()
+        .find(|c| !c.is_ascii_whitespace())
+        .is_none_or(|&c| c == close);
+    if closed && rest.iter().all(u8::is_ascii_whitespace) {
+        Ok(b)
+    } else {
+        Err(Error::Deserialize(consumed))
+    }
+}
+
 bitflags::bitflags! {
     #[repr(transparent)]
     pub struct BufferFlags: u32 {
@@ -485,7 +1612,9 @@ bitflags::bitflags! {
         const REMOVE_DEFAULT_IGNORABLES = sys::HB_BUFFER_FLAG_REMOVE_DEFAULT_IGNORABLES;
         const DO_NOT_INSERT_DOTTED_CIRCLE = sys::HB_BUFFER_FLAG_DO_NOT_INSERT_DOTTED_CIRCLE;
         // const VERIFY = sys::HB_BUFFER_FLAG_VERIFY;
-        // const PRODUCE_UNSAFE_TO_CONCAT = sys::HB_BUFFER_FLAG_PRODUCE_UNSAFE_TO_CONCAT;
+        /// Have the shaper produce [`GlyphFlags::UNSAFE_TO_CONCAT`], which
+        /// it does not do by default as it incurs a cost.
+        const PRODUCE_UNSAFE_TO_CONCAT = sys::HB_BUFFER_FLAG_PRODUCE_UNSAFE_TO_CONCAT;
         // const DEFINED = sys::HB_BUFFER_FLAG_DEFINED;
     }
 }
@@ -495,3 +1624,193 @@ impl Default for BufferFlags {
         BufferFlags::DEFAULT
     }
 }
+
+bitflags::bitflags! {
+    /// The differences found when comparing two buffers with
+    /// [`Buffer::diff`].
+    ///
+    /// The `*_PRESENT` flags describe the reference buffer, i.e. the
+    /// argument of [`Buffer::diff`], whatever the compared buffer holds.
+    /// They are only reported when a dotted circle glyph is given.
+    #[repr(transparent)]
+    pub struct BufferDiffFlags: u32 {
+        /// The buffers are equal.
+        const EQUAL = sys::HB_BUFFER_DIFF_FLAG_EQUAL;
+        /// One buffer holds Unicode characters and the other glyphs.
+        const CONTENT_TYPE_MISMATCH = sys::HB_BUFFER_DIFF_FLAG_CONTENT_TYPE_MISMATCH;
+        /// The buffers have different lengths.
+        const LENGTH_MISMATCH = sys::HB_BUFFER_DIFF_FLAG_LENGTH_MISMATCH;
+        /// The reference buffer contains the `.notdef` glyph.
+        const NOTDEF_PRESENT = sys::HB_BUFFER_DIFF_FLAG_NOTDEF_PRESENT;
+        /// The reference buffer contains the dotted circle glyph.
+        const DOTTED_CIRCLE_PRESENT = sys::HB_BUFFER_DIFF_FLAG_DOTTED_CIRCLE_PRESENT;
+        /// Some codepoints or glyph IDs differ.
+        const CODEPOINT_MISMATCH = sys::HB_BUFFER_DIFF_FLAG_CODEPOINT_MISMATCH;
+        /// Some cluster values differ.
+        const CLUSTER_MISMATCH = sys::HB_BUFFER_DIFF_FLAG_CLUSTER_MISMATCH;
+        /// Some glyph flags differ.
+        const GLYPH_FLAGS_MISMATCH = sys::HB_BUFFER_DIFF_FLAG_GLYPH_FLAGS_MISMATCH;
+        /// Some glyph positions differ by more than the allowed fuzz.
+        const POSITION_MISMATCH = sys::HB_BUFFER_DIFF_FLAG_POSITION_MISMATCH;
+    }
+}
+
+impl BufferDiffFlags {
+    /// Returns true if no difference was found.
+    pub fn is_equal(self) -> bool {
+        self.is_empty()
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags set on the glyphs of a shaped buffer.
+    ///
+    /// They tell which parts of the shaping result can be reused when
+    /// the text is split or changed, e.g. during line breaking.
+    #[repr(transparent)]
+    pub struct GlyphFlags: u32 {
+        /// If the text is broken at the beginning of the cluster this glyph
+        /// is part of, both sides need to be reshaped, as the result might
+        /// differ.
+        const UNSAFE_TO_BREAK = sys::HB_GLYPH_FLAG_UNSAFE_TO_BREAK;
+        /// If the text is changed on one side of the beginning of the
+        /// cluster this glyph is part of, the shaping of the other side
+        /// might change.
+        ///
+        /// Only produced when the buffer has
+        /// [`BufferFlags::PRODUCE_UNSAFE_TO_CONCAT`] set.
+        const UNSAFE_TO_CONCAT = sys::HB_GLYPH_FLAG_UNSAFE_TO_CONCAT;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Buffer;
+    use crate::{
+        shape, Blob, BufferContentType, BufferDiffFlags, Error, Face, Font, SerializeFlags,
+        SerializeFormat,
+    };
+
+    #[test]
+    fn test_add_then_shape() {
+        let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+        let font = Font::new(&mut face);
+        let mut b = Buffer::new();
+        b.add('a' as u32, 0);
+        b.add('b' as u32, 1);
+        assert_eq!(
+            b.serialize(None, SerializeFormat::Text, SerializeFlags::default()),
+            "<U+0061=0|U+0062=1>"
+        );
+        b.guess_segment_properties();
+        shape(&font, &mut b, &[]);
+        assert_eq!(b.content_type(), BufferContentType::Glyphs);
+        assert_eq!(b.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_add_utf16_out_of_bounds() {
+        Buffer::new().add_utf16(&[0x41], 0, Some(1 << 20));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_add_latin1_start_out_of_bounds() {
+        Buffer::new().add_latin1(b"ab", 3, None);
+    }
+
+    #[test]
+    fn test_serialize_empty() {
+        let mut b = Buffer::new();
+        assert_eq!(
+            b.serialize(None, SerializeFormat::Json, SerializeFlags::default()),
+            ""
+        );
+        b.add_str("", 0, None);
+        assert_eq!(
+            b.serialize(None, SerializeFormat::Text, SerializeFlags::default()),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_diff_present_flags_describe_reference() {
+        let a = Buffer::deserialize_glyphs("[5=0+500]", None, SerializeFormat::Text).unwrap();
+        let b = Buffer::deserialize_glyphs("[0=0+500]", None, SerializeFormat::Text).unwrap();
+        assert_eq!(
+            a.diff(&b, Some(9), 0),
+            BufferDiffFlags::NOTDEF_PRESENT | BufferDiffFlags::CODEPOINT_MISMATCH
+        );
+        assert_eq!(b.diff(&a, Some(9), 0), BufferDiffFlags::CODEPOINT_MISMATCH);
+        assert_eq!(a.diff(&b, None, 0), BufferDiffFlags::CODEPOINT_MISMATCH);
+
+        let c = Buffer::deserialize_glyphs("[9=0+500]", None, SerializeFormat::Text).unwrap();
+        assert_eq!(
+            a.diff(&c, Some(9), 0),
+            BufferDiffFlags::DOTTED_CIRCLE_PRESENT | BufferDiffFlags::CODEPOINT_MISMATCH
+        );
+    }
+
+    #[test]
+    fn test_diff_content_type_mismatch() {
+        let glyphs = Buffer::deserialize_glyphs("[97=0+500]", None, SerializeFormat::Text).unwrap();
+        let text = Buffer::with("a");
+        assert_eq!(
+            glyphs.diff(&text, None, 0),
+            BufferDiffFlags::CONTENT_TYPE_MISMATCH
+        );
+        assert_eq!(
+            glyphs.diff_glyphs(&text, 0),
+            [(0, BufferDiffFlags::CONTENT_TYPE_MISMATCH)]
+        );
+        assert_eq!(
+            Buffer::new().diff_glyphs(&text, 0),
+            [(0, BufferDiffFlags::LENGTH_MISMATCH)]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_truncated() {
+        let err = Buffer::deserialize_glyphs("[5=0+500", None, SerializeFormat::Text);
+        assert!(matches!(err, Err(Error::Deserialize(8))));
+        let err = Buffer::deserialize_glyphs(r#"[{"g":5,"cl":0}"#, None, SerializeFormat::Json);
+        assert!(matches!(err, Err(Error::Deserialize(15))));
+        let err = Buffer::deserialize_unicode("<U+0061=0|U+0062=1", SerializeFormat::Text);
+        assert!(matches!(err, Err(Error::Deserialize(18))));
+        let b = Buffer::deserialize_glyphs("[5=0+500] ", None, SerializeFormat::Text).unwrap();
+        assert_eq!(b.len(), 1);
+        let b = Buffer::deserialize_unicode("<U+0061=0>", SerializeFormat::Text).unwrap();
+        assert_eq!(b.len(), 1);
+    }
+
+    #[test]
+    fn test_deserialize_empty() {
+        for format in [SerializeFormat::Text, SerializeFormat::Json] {
+            let b = Buffer::deserialize_glyphs("[]", None, format).unwrap();
+            assert!(b.is_empty());
+            let b = Buffer::deserialize_glyphs(" [ ]\n", None, format).unwrap();
+            assert!(b.is_empty());
+        }
+        let b = Buffer::deserialize_unicode("<>", SerializeFormat::Text).unwrap();
+        assert!(b.is_empty());
+        let b = Buffer::deserialize_unicode("[]", SerializeFormat::Json).unwrap();
+        assert!(b.is_empty());
+        assert!(Buffer::deserialize_unicode("[]", SerializeFormat::Text).is_err());
+        assert!(Buffer::deserialize_glyphs("[", None, SerializeFormat::Text).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "character boundaries")]
+    fn test_add_str_splits_char() {
+        Buffer::new().add_str("café", 0, Some(4));
+    }
+
+    #[test]
+    fn test_add_utf32_item() {
+        let mut b = Buffer::new();
+        b.add_utf32(&['a' as u32, 'b' as u32, 'c' as u32], 1, Some(2));
+        assert_eq!(b.len(), 2);
+        assert_eq!(b.glyph_infos()[0].cluster(), 1);
+    }
+}
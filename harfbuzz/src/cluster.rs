@@ -0,0 +1,217 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ops::Range;
+
+use crate::GlyphInfo;
+
+/// Controls how the cluster values of a [`Buffer`](crate::Buffer) are
+/// merged during shaping.
+///
+/// This maps to the [`hb_buffer_cluster_level_t`] from [`harfbuzz-sys`].
+///
+/// [`hb_buffer_cluster_level_t`]: ../harfbuzz_sys/type.hb_buffer_cluster_level_t.html
+/// [`harfbuzz-sys`]: ../harfbuzz_sys/index.html
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ClusterLevel {
+    /// Clusters are grouped by graphemes, and are monotone in the output
+    /// glyph order. This is the default.
+    #[default]
+    MonotoneGraphemes,
+    /// Clusters are monotone in the output glyph order, but marks and other
+    /// non-base characters keep their own cluster where possible.
+    MonotoneCharacters,
+    /// Clusters are not merged at all, so every character keeps its own
+    /// cluster value, even if that breaks monotonicity.
+    Characters,
+}
+
+impl From<sys::hb_buffer_cluster_level_t> for ClusterLevel {
+    fn from(s: sys::hb_buffer_cluster_level_t) -> Self {
+        match s {
+            sys::HB_BUFFER_CLUSTER_LEVEL_MONOTONE_CHARACTERS => ClusterLevel::MonotoneCharacters,
+            sys::HB_BUFFER_CLUSTER_LEVEL_CHARACTERS => ClusterLevel::Characters,
+            _ => ClusterLevel::MonotoneGraphemes,
+        }
+    }
+}
+
+impl From<ClusterLevel> for sys::hb_buffer_cluster_level_t {
+    fn from(s: ClusterLevel) -> Self {
+        match s {
+            ClusterLevel::MonotoneGraphemes => sys::HB_BUFFER_CLUSTER_LEVEL_MONOTONE_GRAPHEMES,
+            ClusterLevel::MonotoneCharacters => sys::HB_BUFFER_CLUSTER_LEVEL_MONOTONE_CHARACTERS,
+            ClusterLevel::Characters => sys::HB_BUFFER_CLUSTER_LEVEL_CHARACTERS,
+        }
+    }
+}
+
+/// A run of consecutive glyphs, and the text they were shaped from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cluster {
+    /// Indices of the glyphs in the shaped buffer.
+    pub glyphs: Range<usize>,
+    /// The part of the text the glyphs represent, in the units the text
+    /// was added in, e.g. bytes for UTF-8.
+    pub text: Range<usize>,
+}
+
+/// The mapping between shaped glyphs and the text they came from.
+///
+/// Glyphs and characters relate many-to-many: a ligature is one glyph
+/// for several characters, and a decomposed character may produce
+/// several glyphs. A `ClusterMap` groups consecutive glyphs with the same
+/// cluster value, and gives each group the text from its cluster value up
+/// to the next larger cluster value in the buffer.
+///
+/// Glyphs are in visual order, so in right-to-left text the clusters
+/// decrease along the glyphs. With [`ClusterLevel::Characters`], glyphs
+/// of one cluster need not be adjacent, and several [`Cluster`]s can then
+/// share the same text.
+///
+/// ```
+/// # use harfbuzz::{Buffer, Cluster, ClusterMap};
+/// // A hand-made right-to-left run: a ligature of the text 4..7 and a
+/// // base with a mark for the text 0..4.
+/// let mut b = Buffer::new();
+/// b.add(1, 4);
+/// b.add(2, 0);
+/// b.add(3, 0);
+///
+/// let map = ClusterMap::new(b.glyph_infos(), 7);
+/// assert_eq!(
+///     map.clusters(),
+///     [
+///         Cluster { glyphs: 0..1, text: 4..7 },
+///         Cluster { glyphs: 1..3, text: 0..4 },
+///     ]
+/// );
+/// assert_eq!(map.text_range(2), Some(0..4));
+/// assert_eq!(map.glyph_ranges(5).next(), Some(0..1));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClusterMap {
+    clusters: Vec<Cluster>,
+}
+
+impl ClusterMap {
+    /// Build the mapping for the glyphs `infos` of a shaped buffer.
+    ///
+    /// `text_end` is the end of the text that was shaped, which is where
+    /// the cluster with the largest value ends. When the text was added
+    /// with context, this is the end of the item rather than of the
+    /// whole text.
+    pub fn new(infos: &[GlyphInfo], text_end: usize) -> Self {
+        let mut starts: Vec<usize> = infos.iter().map(|info| info.cluster() as usize).collect();
+        starts.sort_unstable();
+        starts.dedup();
+
+        let mut clusters = Vec::new();
+        let mut start = 0;
+        while start < infos.len() {
+            let cluster = infos[start].cluster();
+            let mut end = start + 1;
+            while end < infos.len() && infos[end].cluster() == cluster {
+                end += 1;
+            }
+
+            let cluster = cluster as usize;
+            let next = starts.partition_point(|&start| start <= cluster);
+            let next = starts.get(next).copied().unwrap_or(text_end);
+            clusters.push(Cluster {
+                glyphs: start..end,
+                text: cluster..next.max(cluster),
+            });
+            start = end;
+        }
+        ClusterMap { clusters }
+    }
+
+    /// The clusters, in glyph order.
+    pub fn clusters(&self) -> &[Cluster] {
+        &self.clusters
+    }
+
+    /// The text that the glyph at index `glyph` was shaped from.
+    pub fn text_range(&self, glyph: usize) -> Option<Range<usize>> {
+        let i = self
+            .clusters
+            .partition_point(|cluster| cluster.glyphs.end <= glyph);
+        self.clusters
+            .get(i)
+            .filter(|cluster| cluster.glyphs.contains(&glyph))
+            .map(|cluster| cluster.text.clone())
+    }
+
+    /// The glyphs that the text at offset `offset` was shaped into.
+    ///
+    /// There is usually at most one range, but with
+    /// [`ClusterLevel::Characters`] reordering can split the glyphs of
+    /// one cluster.
+    pub fn glyph_ranges(&self, offset: usize) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.clusters
+            .iter()
+            .filter(move |cluster| cluster.text.contains(&offset))
+            .map(|cluster| cluster.glyphs.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cluster, ClusterMap};
+    use crate::Buffer;
+
+    fn map(clusters: &[u32], text_end: usize) -> ClusterMap {
+        let mut b = Buffer::new();
+        for &cluster in clusters {
+            b.add('x' as u32, cluster);
+        }
+        ClusterMap::new(b.glyph_infos(), text_end)
+    }
+
+    #[test]
+    fn test_left_to_right() {
+        let map = map(&[0, 1, 1, 3], 5);
+        assert_eq!(
+            map.clusters(),
+            [
+                Cluster {
+                    glyphs: 0..1,
+                    text: 0..1
+                },
+                Cluster {
+                    glyphs: 1..3,
+                    text: 1..3
+                },
+                Cluster {
+                    glyphs: 3..4,
+                    text: 3..5
+                },
+            ]
+        );
+        assert_eq!(map.text_range(2), Some(1..3));
+        assert_eq!(map.text_range(4), None);
+        assert_eq!(map.glyph_ranges(2).next(), Some(1..3));
+        assert_eq!(map.glyph_ranges(5).count(), 0);
+    }
+
+    #[test]
+    fn test_split_cluster() {
+        let map = map(&[0, 2, 0], 3);
+        assert_eq!(map.glyph_ranges(1).collect::<Vec<_>>(), [0..1, 2..3]);
+        assert_eq!(map.text_range(1), Some(2..3));
+    }
+
+    #[test]
+    fn test_empty() {
+        let map = map(&[], 0);
+        assert!(map.clusters().is_empty());
+        assert_eq!(map.text_range(0), None);
+    }
+}
@@ -4,4 +4,22 @@ use thiserror::Error;
 pub enum Error {
     #[error("No Memory")]
     NoMemory,
+    /// A range does not lie within the text, or does not start and end on
+    /// UTF-8 character boundaries.
+    #[error("Invalid text range {0:?}")]
+    InvalidRange(std::ops::Range<usize>),
+    /// Serialized buffer contents could not be parsed. Holds the byte
+    /// offset at which parsing stopped.
+    #[error("Invalid serialized buffer at byte {0}")]
+    Deserialize(usize),
+    /// None of the requested shapers could shape the buffer.
+    #[error("Shaping failed")]
+    ShapingFailed,
+    /// A shape plan was executed with a font of another face, or on a
+    /// buffer with other segment properties than it was created for.
+    #[error("Shape plan does not match the font or buffer")]
+    ShapePlanMismatch,
+    /// A file could not be opened or read.
+    #[error("Could not read file {0:?}")]
+    ReadFile(std::path::PathBuf),
 }
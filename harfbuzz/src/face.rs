@@ -1,8 +1,12 @@
 use sys;
 
+use std::collections::BTreeSet;
+use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
 use std::rc::Rc;
 
-use crate::{Blob, UserDataKey};
+use crate::layout::TagList;
+use crate::{Blob, Error, LayoutTable, Set, Tag, UserDataKey};
 
 pub struct Face {
     raw: *mut sys::hb_face_t,
@@ -17,6 +21,80 @@ impl Face {
         }
     }
 
+    /// Create a face whose tables are provided by `func` instead of being
+    /// read from a single font file.
+    ///
+    /// `func` is called with the tag of a table when HarfBuzz first needs
+    /// it, and returns `None` if there is no such table. It is dropped
+    /// together with the face.
+    ///
+    /// If `func` panics, HarfBuzz is told the table is absent, and the
+    /// panic is resumed when the function of this crate that made HarfBuzz
+    /// load the table returns, which need not be a method of this face:
+    /// shaping, or creating a [`ShapePlan`](crate::ShapePlan), loads
+    /// tables as well. HarfBuzz keeps treating the table as absent
+    /// afterwards.
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use harfbuzz::{collect_plan_lookups, Blob, Direction, Face, Language, LayoutTable, Script, SegmentProperties};
+    /// let mut head = vec![0; 54];
+    /// head[0..4].copy_from_slice(&[0, 1, 0, 0]);
+    /// head[12..16].copy_from_slice(&0x5F0F3CF5_u32.to_be_bytes());
+    /// head[18..20].copy_from_slice(&2048_u16.to_be_bytes());
+    /// let head = Arc::new(head);
+    ///
+    /// let face = Face::from_table_fn(move |tag| match tag.to_string().as_str() {
+    ///     "head" => Some(Blob::new_from_arc_vec(head.clone())),
+    ///     _ => None,
+    /// });
+    ///
+    /// let broken = Face::from_table_fn(|_| panic!("no tables here"));
+    /// let props = SegmentProperties::new(Direction::LTR, Script::LATIN, Language::from_string("en"));
+    /// let result = std::panic::catch_unwind(|| {
+    ///     collect_plan_lookups(&broken, &props, &[], LayoutTable::Gsub)
+    /// });
+    /// assert!(result.is_err());
+    /// // Other faces are not affected.
+    /// assert_eq!(face.upem(), 2048);
+    /// ```
+    pub fn from_table_fn<F>(func: F) -> Face
+    where
+        F: Fn(Tag) -> Option<Blob<'static>> + 'static,
+    {
+        unsafe extern "C" fn reference_table<F>(
+            _face: *mut sys::hb_face_t,
+            tag: sys::hb_tag_t,
+            user_data: *mut c_void,
+        ) -> *mut sys::hb_blob_t
+        where
+            F: Fn(Tag) -> Option<Blob<'static>>,
+        {
+            let func = &*(user_data as *const F);
+            match panic::catch_unwind(AssertUnwindSafe(|| func(Tag::from(tag)))) {
+                Ok(Some(blob)) => blob.into_raw(),
+                Ok(None) => sys::hb_blob_get_empty(),
+                Err(payload) => {
+                    crate::store_callback_panic(payload);
+                    sys::hb_blob_get_empty()
+                }
+            }
+        }
+
+        unsafe extern "C" fn destroy<F>(user_data: *mut c_void) {
+            drop(Box::from_raw(user_data as *mut F))
+        }
+
+        let func = Box::into_raw(Box::new(func));
+        unsafe {
+            Face::from_raw(sys::hb_face_create_for_tables(
+                Some(reference_table::<F>),
+                func as *mut c_void,
+                Some(destroy::<F>),
+            ))
+        }
+    }
+
     pub unsafe fn from_raw(raw: *mut sys::hb_face_t) -> Face {
         Face { raw }
     }
@@ -32,11 +110,15 @@ impl Face {
     }
 
     pub fn glyph_count(&self) -> usize {
-        unsafe { sys::hb_face_get_glyph_count(self.raw) as usize }
+        let count = unsafe { sys::hb_face_get_glyph_count(self.raw) as usize };
+        crate::resume_callback_panic();
+        count
     }
 
     pub fn upem(&self) -> u32 {
-        unsafe { sys::hb_face_get_upem(self.raw) }
+        let upem = unsafe { sys::hb_face_get_upem(self.raw) };
+        crate::resume_callback_panic();
+        upem
     }
 
     pub fn user_data<T>(&self, _k: &UserDataKey<T>) -> Rc<T> {
@@ -56,7 +138,53 @@ impl Face {
     }
 
     pub fn reference_blob(&self) -> Blob {
-        unsafe { Blob::from_raw(sys::hb_face_reference_blob(self.raw)) }
+        let blob = unsafe { Blob::from_raw(sys::hb_face_reference_blob(self.raw)) };
+        crate::resume_callback_panic();
+        blob
+    }
+
+    /// The tags of the tables in the face, in the order of the font's
+    /// table directory.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Face};
+    /// # let face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// for tag in face.table_tags() {
+    ///     let table = face.table(tag.clone()).unwrap();
+    ///     println!("{}: {} bytes", tag, table.len());
+    /// }
+    /// assert!(face.table("OS/2".parse().unwrap()).is_none());
+    /// ```
+    pub fn table_tags(&self) -> Vec<Tag> {
+        let mut tags = Vec::new();
+        loop {
+            let mut page = [0; 32];
+            let mut count = page.len() as u32;
+            unsafe {
+                sys::hb_face_get_table_tags(
+                    self.raw,
+                    tags.len() as u32,
+                    &mut count,
+                    page.as_mut_ptr(),
+                );
+            }
+            tags.extend(page[..count as usize].iter().map(|&tag| Tag::from(tag)));
+            if count < page.len() as u32 {
+                return tags;
+            }
+        }
+    }
+
+    /// The data of the table `tag`, or `None` if the face has no such
+    /// table, or it is empty.
+    pub fn table(&self, tag: Tag) -> Option<Blob<'_>> {
+        let blob = unsafe { Blob::from_raw(sys::hb_face_reference_table(self.raw, tag.into())) };
+        crate::resume_callback_panic();
+        if blob.is_empty() {
+            None
+        } else {
+            Some(blob)
+        }
     }
 
     pub fn set_glyph_count(&mut self, count: usize) {
@@ -71,6 +199,185 @@ impl Face {
         unsafe { sys::hb_face_set_upem(self.raw, upem) }
     }
 
+    /// The Unicode codepoints the face has a glyph for in its `cmap`
+    /// table.
+    pub fn collect_unicodes(&self) -> BTreeSet<u32> {
+        self.unicodes().iter().collect()
+    }
+
+    fn unicodes(&self) -> Set {
+        let mut set = Set::new();
+        unsafe { sys::hb_face_collect_unicodes(self.raw, set.as_mut_ptr()) };
+        crate::resume_callback_panic();
+        set
+    }
+
+    /// The variation selectors the face has variation sequences for.
+    pub fn collect_variation_selectors(&self) -> BTreeSet<u32> {
+        let mut set = Set::new();
+        unsafe { sys::hb_face_collect_variation_selectors(self.raw, set.as_mut_ptr()) };
+        crate::resume_callback_panic();
+        set.iter().collect()
+    }
+
+    /// The codepoints the face has a variation sequence for with
+    /// `selector`.
+    pub fn collect_variation_unicodes(&self, selector: u32) -> BTreeSet<u32> {
+        let mut set = Set::new();
+        unsafe { sys::hb_face_collect_variation_unicodes(self.raw, selector, set.as_mut_ptr()) };
+        crate::resume_callback_panic();
+        set.iter().collect()
+    }
+
+    /// Whether the face has a glyph for every character of `text`.
+    ///
+    /// See [`first_missing`](#method.first_missing) to find out which
+    /// character is not covered.
+    pub fn covers(&self, text: &str) -> bool {
+        self.first_missing(text).is_none()
+    }
+
+    /// The first character of `text` the face has no glyph for, with its
+    /// byte offset.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Face};
+    /// # let face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// // The face has no glyphs at all.
+    /// assert!(face.collect_unicodes().is_empty());
+    /// assert!(face.covers(""));
+    /// assert!(!face.covers("añb"));
+    /// assert_eq!(face.first_missing("añb"), Some((0, 'a')));
+    /// ```
+    pub fn first_missing(&self, text: &str) -> Option<(usize, char)> {
+        let unicodes = self.unicodes();
+        text.char_indices()
+            .find(|&(_, c)| !unicodes.contains(c as u32))
+    }
+
+    /// The tags of the features in the feature list of `table`.
+    ///
+    /// The feature indices returned by
+    /// [`collect_features`](#method.collect_features) index into this list.
+    pub fn feature_tags(&self, table: LayoutTable) -> Vec<Tag> {
+        let mut tags = Vec::new();
+        loop {
+            let mut page = [0; 32];
+            let mut count = page.len() as u32;
+            unsafe {
+                sys::hb_ot_layout_table_get_feature_tags(
+                    self.raw,
+                    table.into(),
+                    tags.len() as u32,
+                    &mut count,
+                    page.as_mut_ptr(),
+                );
+            }
+            crate::resume_callback_panic();
+            tags.extend(page[..count as usize].iter().map(|&tag| Tag::from(tag)));
+            if count < page.len() as u32 {
+                return tags;
+            }
+        }
+    }
+
+    /// The indices of the features in `table` that belong to the given
+    /// scripts and languages and have one of the given tags.
+    ///
+    /// `None` for any of the lists means all of them. When `languages` is
+    /// given, the default language system of the scripts is not included.
+    pub fn collect_features(
+        &self,
+        table: LayoutTable,
+        scripts: Option<&[Tag]>,
+        languages: Option<&[Tag]>,
+        features: Option<&[Tag]>,
+    ) -> BTreeSet<u32> {
+        let scripts = TagList::new(scripts);
+        let languages = TagList::new(languages);
+        let features = TagList::new(features);
+        let mut set = Set::new();
+        unsafe {
+            sys::hb_ot_layout_collect_features(
+                self.raw,
+                table.into(),
+                scripts.as_ptr(),
+                languages.as_ptr(),
+                features.as_ptr(),
+                set.as_mut_ptr(),
+            )
+        };
+        crate::resume_callback_panic();
+        set.iter().collect()
+    }
+
+    /// The indices of the lookups in `table` that are referenced by the
+    /// features [`collect_features`](#method.collect_features) finds for
+    /// the same arguments.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Face, LayoutTable, Tag};
+    /// # let face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// let latn = "latn".parse::<Tag>().unwrap();
+    /// let liga = "liga".parse::<Tag>().unwrap();
+    /// let features = face.collect_features(LayoutTable::Gsub, Some(&[latn.clone()]), None, None);
+    /// let lookups = face.collect_lookups(LayoutTable::Gsub, Some(&[latn]), None, Some(&[liga]));
+    /// // The face has no GSUB table.
+    /// assert!(features.is_empty() && lookups.is_empty());
+    /// assert!(face.feature_tags(LayoutTable::Gsub).is_empty());
+    /// ```
+    pub fn collect_lookups(
+        &self,
+        table: LayoutTable,
+        scripts: Option<&[Tag]>,
+        languages: Option<&[Tag]>,
+        features: Option<&[Tag]>,
+    ) -> BTreeSet<u32> {
+        let scripts = TagList::new(scripts);
+        let languages = TagList::new(languages);
+        let features = TagList::new(features);
+        let mut set = Set::new();
+        unsafe {
+            sys::hb_ot_layout_collect_lookups(
+                self.raw,
+                table.into(),
+                scripts.as_ptr(),
+                languages.as_ptr(),
+                features.as_ptr(),
+                set.as_mut_ptr(),
+            )
+        };
+        crate::resume_callback_panic();
+        set.iter().collect()
+    }
+
+    /// The glyphs that applying the GSUB `lookups` can produce from
+    /// `glyphs`, including `glyphs` themselves.
+    ///
+    /// The lookups are applied recursively, so the result is closed under
+    /// substitution: e.g. an alternate of a ligature is included.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Face, LayoutTable};
+    /// # let face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// let lookups = face.collect_lookups(LayoutTable::Gsub, None, None, None);
+    /// let closure = face.gsub_closure(&lookups, &[1, 2].into());
+    /// assert_eq!(closure, [1, 2].into());
+    /// ```
+    pub fn gsub_closure(&self, lookups: &BTreeSet<u32>, glyphs: &BTreeSet<u32>) -> BTreeSet<u32> {
+        let lookups: Set = lookups.iter().copied().collect();
+        let mut glyphs: Set = glyphs.iter().copied().collect();
+        unsafe {
+            sys::hb_ot_layout_lookups_substitute_closure(
+                self.raw,
+                lookups.as_ptr(),
+                glyphs.as_mut_ptr(),
+            )
+        };
+        crate::resume_callback_panic();
+        glyphs.iter().collect()
+    }
+
     pub fn as_ptr(&self) -> *const sys::hb_face_t {
         self.raw
     }
@@ -80,6 +387,77 @@ impl Face {
     }
 }
 
+/// Builds a face from individual tables, e.g. to make a font for tests
+/// or to replace tables of an existing font.
+///
+/// The result can be used as a [`Face`] directly, or serialized to a font
+/// file with [`reference_blob`](#method.reference_blob).
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use harfbuzz::{Blob, Face, FaceBuilder};
+/// let mut head = vec![0; 54];
+/// head[0..4].copy_from_slice(&[0, 1, 0, 0]);
+/// head[12..16].copy_from_slice(&0x5F0F3CF5_u32.to_be_bytes());
+/// head[18..20].copy_from_slice(&1000_u16.to_be_bytes());
+///
+/// let mut builder = FaceBuilder::new();
+/// builder
+///     .add_table("head".parse().unwrap(), &Blob::new_from_arc_vec(Arc::new(head)))
+///     .unwrap();
+///
+/// let font_file = builder.reference_blob();
+/// let face = Face::new(&font_file, 0);
+/// assert_eq!(face.upem(), 1000);
+/// assert_eq!(face.table_tags().len(), 1);
+///
+/// assert_eq!(builder.into_face().upem(), 1000);
+/// ```
+pub struct FaceBuilder {
+    face: Face,
+}
+
+impl FaceBuilder {
+    /// Create a builder with no tables.
+    pub fn new() -> FaceBuilder {
+        unsafe {
+            FaceBuilder {
+                face: Face::from_raw(sys::hb_face_builder_create()),
+            }
+        }
+    }
+
+    /// Add the table `tag` with the data of `blob`, replacing any table
+    /// with the same tag.
+    ///
+    /// The blob is referenced, not copied.
+    pub fn add_table(&mut self, tag: Tag, blob: &Blob<'static>) -> Result<(), Error> {
+        let ok =
+            unsafe { sys::hb_face_builder_add_table(self.face.raw, tag.into(), blob.as_raw()) };
+        if ok != 0 {
+            Ok(())
+        } else {
+            Err(Error::NoMemory)
+        }
+    }
+
+    /// Serialize the tables added so far to a font file.
+    pub fn reference_blob(&self) -> Blob<'static> {
+        unsafe { Blob::from_raw(sys::hb_face_reference_blob(self.face.raw)) }
+    }
+
+    /// Turn the builder into a face with the tables added so far.
+    pub fn into_face(self) -> Face {
+        self.face
+    }
+}
+
+impl Default for FaceBuilder {
+    fn default() -> FaceBuilder {
+        FaceBuilder::new()
+    }
+}
+
 impl Drop for Face {
     fn drop(&mut self) {
         unsafe {
@@ -1,9 +1,10 @@
+use std::collections::BTreeSet;
 use std::ops::Deref;
 use std::ptr::NonNull;
 
 use sys;
 
-use crate::{Codepoint, Direction, Face, FontExtents};
+use crate::{Buffer, Codepoint, Direction, Face, Feature, FontExtents, Set};
 
 #[repr(transparent)]
 pub struct FontMut(NonNull<sys::hb_font_t>);
@@ -13,13 +14,17 @@ pub struct Font(NonNull<sys::hb_font_t>);
 
 impl Font {
     pub fn new(face: &mut Face) -> FontMut {
-        unsafe { FontMut(NonNull::new(sys::hb_font_create(face.as_mut_ptr())).unwrap()) }
+        FontMut::new(face)
     }
 }
 
 impl FontMut {
     pub fn new(face: &mut Face) -> FontMut {
-        unsafe { FontMut(NonNull::new(sys::hb_font_create(face.as_mut_ptr())).unwrap()) }
+        let font =
+            unsafe { FontMut(NonNull::new(sys::hb_font_create(face.as_mut_ptr())).unwrap()) };
+        // Creating a font reads the `head` and `maxp` tables.
+        crate::resume_callback_panic();
+        font
     }
 
     #[doc(hidden)]
@@ -40,18 +45,21 @@ impl FontMut {
         unsafe {
             sys::hb_font_set_ppem(self.as_mut_ptr(), x_ppem, y_ppem);
         }
+        crate::resume_callback_panic();
     }
 
     pub fn set_ptem(&mut self, ptem: f32) {
         unsafe {
             sys::hb_font_set_ptem(self.as_mut_ptr(), ptem);
         }
+        crate::resume_callback_panic();
     }
 
     pub fn set_scale(&mut self, x_scale: i32, y_scale: i32) {
         unsafe {
             sys::hb_font_set_scale(self.as_mut_ptr(), x_scale, y_scale);
         }
+        crate::resume_callback_panic();
     }
 
     // pub fn set_synthetic_slant(&mut self, synthetic_slant: f32) {
@@ -94,6 +102,7 @@ impl Font {
         let is_found = unsafe {
             sys::hb_font_get_glyph(self.as_mut_ptr(), unicode, variation_selector, &mut glyph)
         };
+        crate::resume_callback_panic();
         if is_found != 0 {
             Some(glyph)
         } else {
@@ -111,6 +120,7 @@ impl Font {
                 &mut glyph,
             )
         };
+        crate::resume_callback_panic();
         if is_found != 0 {
             Some(glyph)
         } else {
@@ -141,7 +151,7 @@ impl Font {
     }
 
     pub fn extents_for_direction(&self, direction: &Direction) -> FontExtents {
-        unsafe {
+        let extents = unsafe {
             let mut extents: std::mem::MaybeUninit<sys::hb_font_extents_t> =
                 std::mem::MaybeUninit::zeroed();
             sys::hb_font_get_extents_for_direction(
@@ -150,7 +160,9 @@ impl Font {
                 extents.as_mut_ptr(),
             );
             extents.assume_init().into()
-        }
+        };
+        crate::resume_callback_panic();
+        extents
     }
 
     // pub fn serial(&self) -> u32 {
@@ -161,6 +173,38 @@ impl Font {
     //     unsafe { sys::hb_font_changed(self.as_mut_ptr()) }
     // }
 
+    /// The glyphs that shaping the text in `buf` with `features` can
+    /// produce, such as ligatures and alternates.
+    ///
+    /// This includes the glyphs of all the alternatives a feature could
+    /// choose from, so it is a superset of what shaping actually gives.
+    /// The segment properties of `buf` must be set, as they decide which
+    /// lookups apply.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Buffer, Face, Font};
+    /// # let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// # let font = Font::new(&mut face);
+    /// let mut b = Buffer::with("fi");
+    /// b.guess_segment_properties();
+    /// // The face has no glyphs for the text.
+    /// assert!(font.glyph_closure(&b, &[]).is_empty());
+    /// ```
+    pub fn glyph_closure(&self, buf: &Buffer, features: &[Feature]) -> BTreeSet<u32> {
+        let mut glyphs = Set::new();
+        unsafe {
+            sys::hb_ot_shape_glyphs_closure(
+                self.as_mut_ptr(),
+                buf.as_mut_ptr(),
+                features.as_ptr() as *const sys::hb_feature_t,
+                features.len().try_into().unwrap(),
+                glyphs.as_mut_ptr(),
+            )
+        };
+        crate::resume_callback_panic();
+        glyphs.iter().collect()
+    }
+
     pub fn is_immutable(&self) -> bool {
         unsafe { sys::hb_font_is_immutable(self.as_mut_ptr()) != 0 }
     }
@@ -171,7 +215,11 @@ impl Font {
     }
 
     pub fn sub_font(&self) -> FontMut {
-        unsafe { FontMut(NonNull::new(sys::hb_font_create_sub_font(self.as_mut_ptr())).unwrap()) }
+        let font = unsafe {
+            FontMut(NonNull::new(sys::hb_font_create_sub_font(self.as_mut_ptr())).unwrap())
+        };
+        crate::resume_callback_panic();
+        font
     }
 
     /// Borrows a raw pointer to the font.
@@ -61,7 +61,11 @@ impl Language {
 
 impl std::fmt::Debug for Language {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        fmt.write_str(self.to_string())
+        if self.is_valid() {
+            fmt.write_str(self.to_string())
+        } else {
+            fmt.write_str("<invalid>")
+        }
     }
 }
 
@@ -0,0 +1,81 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::BTreeSet;
+
+use crate::{Face, Feature, SegmentProperties, ShapePlan, Tag};
+
+/// One of the OpenType layout tables that hold lookups.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LayoutTable {
+    /// The glyph substitution table, `GSUB`.
+    Gsub,
+    /// The glyph positioning table, `GPOS`.
+    Gpos,
+}
+
+impl LayoutTable {
+    /// The tag of the table.
+    pub fn tag(self) -> Tag {
+        sys::hb_tag_t::from(self).into()
+    }
+}
+
+#[doc(hidden)]
+impl From<LayoutTable> for sys::hb_tag_t {
+    fn from(table: LayoutTable) -> Self {
+        match table {
+            LayoutTable::Gsub => u32::from_be_bytes(*b"GSUB"),
+            LayoutTable::Gpos => u32::from_be_bytes(*b"GPOS"),
+        }
+    }
+}
+
+/// The indices of the lookups in `table` that shaping text with the
+/// segment properties `props` and `features` applies.
+///
+/// This creates, or takes from the cache of `face`, the shape plan for
+/// the arguments and asks it for its lookups; see
+/// [`ShapePlan::collect_lookups`].
+///
+/// ```
+/// # use harfbuzz::{collect_plan_lookups, Blob, Direction, Face, Language, LayoutTable, Script, SegmentProperties};
+/// # let face = Face::new(&Blob::new_read_only(&[]), 0);
+/// let props = SegmentProperties::new(Direction::LTR, Script::LATIN, Language::from_string("en"));
+/// let lookups = collect_plan_lookups(&face, &props, &[], LayoutTable::Gsub);
+/// // The face has no GSUB table.
+/// assert!(lookups.is_empty());
+/// ```
+pub fn collect_plan_lookups(
+    face: &Face,
+    props: &SegmentProperties,
+    features: &[Feature],
+    table: LayoutTable,
+) -> BTreeSet<u32> {
+    ShapePlan::new(face, props, features, &[], None).collect_lookups(table)
+}
+
+/// A `HB_TAG_NONE` terminated copy of `tags`, or `None` for a null list.
+pub(crate) struct TagList(Option<Vec<sys::hb_tag_t>>);
+
+impl TagList {
+    pub(crate) fn new(tags: Option<&[Tag]>) -> Self {
+        TagList(tags.map(|tags| {
+            let mut list: Vec<sys::hb_tag_t> = tags.iter().map(|tag| tag.clone().into()).collect();
+            list.push(0);
+            list
+        }))
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const sys::hb_tag_t {
+        self.0
+            .as_ref()
+            .map_or(std::ptr::null(), |list| list.as_ptr())
+    }
+}
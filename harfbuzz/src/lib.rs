@@ -20,11 +20,19 @@
 
 pub use sys;
 
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
 #[macro_use]
 mod user_data;
 
 mod blob;
 mod buffer;
+mod cluster;
 mod direction;
 mod errors;
 mod face;
@@ -32,23 +40,70 @@ mod feature;
 mod font;
 mod font_extents;
 mod language;
+mod layout;
+mod map;
+mod script;
+mod segment_properties;
+mod serialize;
+mod set;
+mod shape_plan;
 mod tag;
 
 pub use blob::Blob;
-pub use buffer::{Buffer, BufferFlags};
+pub use buffer::{
+    Buffer, BufferContentType, BufferDiffFlags, BufferFlags, GlyphBuffer, GlyphFlags, GlyphInfo,
+    GlyphPosition, TextItem, UnicodeBuffer,
+};
+pub use cluster::{Cluster, ClusterLevel, ClusterMap};
 pub use direction::Direction;
 pub use errors::Error;
-pub use face::Face;
+pub use face::{Face, FaceBuilder};
 pub use feature::Feature;
 pub use font::{Font, FontMut};
 pub use font_extents::FontExtents;
 pub use language::Language;
+pub use layout::{collect_plan_lookups, LayoutTable};
+pub use map::Map;
+pub use script::Script;
+pub use segment_properties::SegmentProperties;
+pub use serialize::{SerializeFlags, SerializeFormat};
+pub use set::Set;
+pub use shape_plan::ShapePlan;
 pub use tag::Tag;
 pub use user_data::UserDataKey;
 
 /// Data type for holding Unicode codepoints. Also used to hold glyph IDs.
 pub type Codepoint = sys::hb_codepoint_t;
 
+thread_local! {
+    /// A panic caught in a callback from HarfBuzz, such as a message or
+    /// table function, to be resumed once the call into HarfBuzz returns.
+    ///
+    /// HarfBuzz calls back on the thread that called into it, so catching
+    /// and resuming happen on the same thread.
+    static CALLBACK_PANIC: RefCell<Option<Box<dyn Any + Send>>> = RefCell::new(None);
+}
+
+/// Whether a callback panicked during the current call into HarfBuzz.
+pub(crate) fn callback_panicked() -> bool {
+    CALLBACK_PANIC.with(|panic| panic.borrow().is_some())
+}
+
+/// Keep the payload of a panic caught in a callback, unless one is kept
+/// already.
+pub(crate) fn store_callback_panic(payload: Box<dyn Any + Send>) {
+    CALLBACK_PANIC.with(|panic| {
+        panic.borrow_mut().get_or_insert(payload);
+    });
+}
+
+/// Resume a panic caught in a callback, if any.
+pub(crate) fn resume_callback_panic() {
+    if let Some(payload) = CALLBACK_PANIC.with(|panic| panic.borrow_mut().take()) {
+        panic::resume_unwind(payload);
+    }
+}
+
 /// Shapes buffer using font turning its Unicode characters content to positioned glyphs.
 /// If features is not NULL, it will be used to control the features applied during shaping.
 /// If two features have the same tag but overlapping ranges the value of the feature
@@ -62,4 +117,118 @@ pub fn shape(font: &Font, buf: &mut Buffer, features: &[Feature]) {
             features.len().try_into().unwrap(),
         );
     }
+    resume_callback_panic();
+}
+
+/// Shapes buffer like [`shape`], trying only the shapers named in `shapers`.
+///
+/// The shapers are tried in order until one succeeds. If `shapers` is
+/// `None`, all shapers returned by [`list_shapers`] are tried, which is what
+/// [`shape`] does. Names that are not known shapers are skipped.
+///
+/// Returns [`Error::ShapingFailed`] if none of the shapers succeeded, in
+/// which case the buffer is left unshaped.
+///
+/// ```
+/// # use harfbuzz::{shape_full, Blob, Buffer, Error, Face, Font};
+/// # let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+/// # let font = Font::new(&mut face);
+/// let mut b = Buffer::with("ab");
+/// b.guess_segment_properties();
+/// shape_full(&font, &mut b, &[], Some(&["fallback"])).unwrap();
+/// assert_eq!(b.len(), 2);
+///
+/// let mut b = Buffer::with("ab");
+/// b.guess_segment_properties();
+/// let result = shape_full(&font, &mut b, &[], Some(&["no-such-shaper"]));
+/// assert!(matches!(result, Err(Error::ShapingFailed)));
+/// ```
+pub fn shape_full(
+    font: &Font,
+    buf: &mut Buffer,
+    features: &[Feature],
+    shapers: Option<&[&str]>,
+) -> Result<(), Error> {
+    let shapers = shape_plan::ShaperList::new(shapers);
+    let ok = unsafe {
+        sys::hb_shape_full(
+            font.as_ptr() as *mut sys::hb_font_t,
+            buf.as_mut_ptr(),
+            features.as_ptr() as *mut sys::hb_feature_t,
+            features.len().try_into().unwrap(),
+            shapers.as_ptr(),
+        )
+    };
+    resume_callback_panic();
+    if ok != 0 {
+        Ok(())
+    } else {
+        Err(Error::ShapingFailed)
+    }
+}
+
+/// The names of the shapers HarfBuzz was built with, in the order they
+/// are tried.
+///
+/// ```
+/// # use harfbuzz::list_shapers;
+/// let shapers = list_shapers();
+/// assert!(shapers.contains(&"ot"));
+/// assert_eq!(shapers.last(), Some(&"fallback"));
+/// ```
+pub fn list_shapers() -> Vec<&'static str> {
+    let mut shapers = Vec::new();
+    unsafe {
+        let mut list = sys::hb_shape_list_shapers() as *const *const c_char;
+        while !(*list).is_null() {
+            if let Ok(name) = CStr::from_ptr(*list).to_str() {
+                shapers.push(name);
+            }
+            list = list.add(1);
+        }
+    }
+    shapers
+}
+
+/// Shapes buffer like [`shape`], and records the state of the buffer at
+/// each step of shaping.
+///
+/// Returns the messages sent by HarfBuzz while shaping, such as
+/// `start lookup 3`, each with the buffer serialized as text at that
+/// point.
+///
+/// This installs its own message function on the buffer, so any function
+/// set with [`Buffer::set_message_func`] is dropped, and the buffer has
+/// no message function afterwards, even if shaping panics.
+///
+/// ```
+/// # use harfbuzz::{shape_with_trace, Blob, Buffer, Face, Font};
+/// # let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+/// # let font = Font::new(&mut face);
+/// let mut b = Buffer::with("ab");
+/// b.guess_segment_properties();
+/// let trace = shape_with_trace(&font, &mut b, &[]);
+/// let (message, buffer) = &trace[0];
+/// assert_eq!(message, "start table GSUB");
+/// assert_eq!(buffer, "[gid0=0|gid0=1]");
+/// ```
+pub fn shape_with_trace(
+    font: &Font,
+    buf: &mut Buffer,
+    features: &[Feature],
+) -> Vec<(String, String)> {
+    let trace = Rc::new(RefCell::new(Vec::new()));
+    let sink = trace.clone();
+    buf.set_message_func(move |buffer, font, message| {
+        let serialized =
+            buffer.serialize(Some(font), SerializeFormat::Text, SerializeFlags::default());
+        sink.borrow_mut().push((message.to_owned(), serialized));
+        true
+    });
+    let result = panic::catch_unwind(AssertUnwindSafe(|| shape(font, buf, features)));
+    buf.clear_message_func();
+    if let Err(payload) = result {
+        panic::resume_unwind(payload);
+    }
+    Rc::try_unwrap(trace).unwrap().into_inner()
 }
@@ -0,0 +1,184 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::hash::{Hash, Hasher};
+
+use crate::Error;
+
+/// A hash map from `u32` to `u32`, such as a mapping of old glyph IDs to
+/// new ones.
+///
+/// ```
+/// # use harfbuzz::Map;
+/// let mut map: Map = [(1, 10), (2, 20)].into_iter().collect();
+/// map.insert(3, 30);
+/// map.remove(1);
+/// assert_eq!(map.get(2), Some(20));
+/// assert_eq!(map.get(1), None);
+/// assert!(map.contains_key(3));
+/// assert_eq!(map.len(), 2);
+/// ```
+///
+/// A map that failed to allocate memory ignores further changes;
+/// [`check_allocation`](#method.check_allocation) tells whether that
+/// happened.
+///
+/// This maps to the [`hb_map_t`] from [`harfbuzz-sys`].
+///
+/// [`hb_map_t`]: ../harfbuzz_sys/struct.hb_map_t.html
+/// [`harfbuzz-sys`]: ../harfbuzz_sys/index.html
+pub struct Map {
+    raw: *mut sys::hb_map_t,
+}
+
+impl Map {
+    /// Create an empty map.
+    pub fn new() -> Map {
+        unsafe { Map::from_raw(sys::hb_map_create()) }
+    }
+
+    /// Whether all allocations of the map succeeded.
+    ///
+    /// Returns [`Error::NoMemory`] if one failed, after which the map is
+    /// left unchanged by any operation that needs to allocate.
+    pub fn check_allocation(&self) -> Result<(), Error> {
+        if unsafe { sys::hb_map_allocation_successful(self.raw) } != 0 {
+            Ok(())
+        } else {
+            Err(Error::NoMemory)
+        }
+    }
+
+    /// The number of entries in the map.
+    pub fn len(&self) -> usize {
+        unsafe { sys::hb_map_get_population(self.raw) as usize }
+    }
+
+    /// Whether the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        unsafe { sys::hb_map_is_empty(self.raw) != 0 }
+    }
+
+    /// The value for `key`.
+    pub fn get(&self, key: u32) -> Option<u32> {
+        if self.contains_key(key) {
+            Some(unsafe { sys::hb_map_get(self.raw, key) })
+        } else {
+            None
+        }
+    }
+
+    /// Whether the map has an entry for `key`.
+    pub fn contains_key(&self, key: u32) -> bool {
+        unsafe { sys::hb_map_has(self.raw, key) != 0 }
+    }
+
+    /// Set the value for `key`, replacing any previous one.
+    pub fn insert(&mut self, key: u32, value: u32) {
+        unsafe { sys::hb_map_set(self.raw, key, value) }
+    }
+
+    /// Remove the entry for `key`.
+    pub fn remove(&mut self, key: u32) {
+        unsafe { sys::hb_map_del(self.raw, key) }
+    }
+
+    /// Remove all entries.
+    pub fn clear(&mut self) {
+        unsafe { sys::hb_map_clear(self.raw) }
+    }
+
+    /// Wrap a raw `hb_map_t`, taking over its reference.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid pointer to an `hb_map_t`.
+    pub unsafe fn from_raw(raw: *mut sys::hb_map_t) -> Map {
+        Map { raw }
+    }
+
+    /// Give up the map, returning its raw `hb_map_t` with its reference.
+    pub fn into_raw(self) -> *mut sys::hb_map_t {
+        let raw = self.raw;
+        std::mem::forget(self);
+        raw
+    }
+
+    /// The underlying `hb_map_t`.
+    pub fn as_ptr(&self) -> *const sys::hb_map_t {
+        self.raw
+    }
+
+    /// The underlying `hb_map_t`, for changing it.
+    pub fn as_mut_ptr(&mut self) -> *mut sys::hb_map_t {
+        self.raw
+    }
+}
+
+impl Default for Map {
+    fn default() -> Map {
+        Map::new()
+    }
+}
+
+impl Clone for Map {
+    /// Copy the entries into a new map.
+    fn clone(&self) -> Map {
+        unsafe {
+            let raw = sys::hb_map_copy(self.raw);
+            if raw.is_null() {
+                Map::from_raw(sys::hb_map_get_empty())
+            } else {
+                Map::from_raw(raw)
+            }
+        }
+    }
+}
+
+impl Drop for Map {
+    fn drop(&mut self) {
+        unsafe { sys::hb_map_destroy(self.raw) }
+    }
+}
+
+impl PartialEq for Map {
+    fn eq(&self, other: &Map) -> bool {
+        unsafe { sys::hb_map_is_equal(self.raw, other.raw) != 0 }
+    }
+}
+
+impl Eq for Map {}
+
+impl Hash for Map {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u32(unsafe { sys::hb_map_hash(self.raw) });
+    }
+}
+
+impl std::fmt::Debug for Map {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Map").field("len", &self.len()).finish()
+    }
+}
+
+impl FromIterator<(u32, u32)> for Map {
+    fn from_iter<I: IntoIterator<Item = (u32, u32)>>(iter: I) -> Map {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl Extend<(u32, u32)> for Map {
+    fn extend<I: IntoIterator<Item = (u32, u32)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
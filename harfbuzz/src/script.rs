@@ -0,0 +1,460 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::str::FromStr;
+
+use crate::{Direction, Tag};
+
+/// A writing system, as identified by its [ISO 15924] code.
+///
+/// This maps to the [`hb_script_t`] from [`harfbuzz-sys`]. Scripts
+/// can be created from their four letter ISO 15924 code, and are
+/// displayed as that code:
+///
+/// ```
+/// # use harfbuzz::Script;
+/// let script: Script = "Arab".parse().unwrap();
+/// assert_eq!(script, Script::ARABIC);
+/// assert_eq!(script.to_string(), "Arab");
+///
+/// // Parsing is case-insensitive.
+/// assert_eq!("latn".parse(), Ok(Script::LATIN));
+/// ```
+///
+/// [ISO 15924]: https://unicode.org/iso15924/
+/// [`hb_script_t`]: ../harfbuzz_sys/type.hb_script_t.html
+/// [`harfbuzz-sys`]: ../harfbuzz_sys/index.html
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct Script(sys::hb_script_t);
+
+impl Script {
+    /// Initial, unset script.
+    pub const INVALID: Script = Script(sys::HB_SCRIPT_INVALID);
+    /// Common (`Zyyy`).
+    pub const COMMON: Script = Script(sys::HB_SCRIPT_COMMON);
+    /// Inherited (`Zinh`).
+    pub const INHERITED: Script = Script(sys::HB_SCRIPT_INHERITED);
+    /// Unknown (`Zzzz`).
+    pub const UNKNOWN: Script = Script(sys::HB_SCRIPT_UNKNOWN);
+    /// Arabic (`Arab`).
+    pub const ARABIC: Script = Script(sys::HB_SCRIPT_ARABIC);
+    /// Armenian (`Armn`).
+    pub const ARMENIAN: Script = Script(sys::HB_SCRIPT_ARMENIAN);
+    /// Bengali (`Beng`).
+    pub const BENGALI: Script = Script(sys::HB_SCRIPT_BENGALI);
+    /// Cyrillic (`Cyrl`).
+    pub const CYRILLIC: Script = Script(sys::HB_SCRIPT_CYRILLIC);
+    /// Devanagari (`Deva`).
+    pub const DEVANAGARI: Script = Script(sys::HB_SCRIPT_DEVANAGARI);
+    /// Georgian (`Geor`).
+    pub const GEORGIAN: Script = Script(sys::HB_SCRIPT_GEORGIAN);
+    /// Greek (`Grek`).
+    pub const GREEK: Script = Script(sys::HB_SCRIPT_GREEK);
+    /// Gujarati (`Gujr`).
+    pub const GUJARATI: Script = Script(sys::HB_SCRIPT_GUJARATI);
+    /// Gurmukhi (`Guru`).
+    pub const GURMUKHI: Script = Script(sys::HB_SCRIPT_GURMUKHI);
+    /// Hangul (`Hang`).
+    pub const HANGUL: Script = Script(sys::HB_SCRIPT_HANGUL);
+    /// Han (`Hani`).
+    pub const HAN: Script = Script(sys::HB_SCRIPT_HAN);
+    /// Hebrew (`Hebr`).
+    pub const HEBREW: Script = Script(sys::HB_SCRIPT_HEBREW);
+    /// Hiragana (`Hira`).
+    pub const HIRAGANA: Script = Script(sys::HB_SCRIPT_HIRAGANA);
+    /// Kannada (`Knda`).
+    pub const KANNADA: Script = Script(sys::HB_SCRIPT_KANNADA);
+    /// Katakana (`Kana`).
+    pub const KATAKANA: Script = Script(sys::HB_SCRIPT_KATAKANA);
+    /// Lao (`Laoo`).
+    pub const LAO: Script = Script(sys::HB_SCRIPT_LAO);
+    /// Latin (`Latn`).
+    pub const LATIN: Script = Script(sys::HB_SCRIPT_LATIN);
+    /// Malayalam (`Mlym`).
+    pub const MALAYALAM: Script = Script(sys::HB_SCRIPT_MALAYALAM);
+    /// Oriya (`Orya`).
+    pub const ORIYA: Script = Script(sys::HB_SCRIPT_ORIYA);
+    /// Tamil (`Taml`).
+    pub const TAMIL: Script = Script(sys::HB_SCRIPT_TAMIL);
+    /// Telugu (`Telu`).
+    pub const TELUGU: Script = Script(sys::HB_SCRIPT_TELUGU);
+    /// Thai (`Thai`).
+    pub const THAI: Script = Script(sys::HB_SCRIPT_THAI);
+    /// Tibetan (`Tibt`).
+    pub const TIBETAN: Script = Script(sys::HB_SCRIPT_TIBETAN);
+    /// Bopomofo (`Bopo`).
+    pub const BOPOMOFO: Script = Script(sys::HB_SCRIPT_BOPOMOFO);
+    /// Braille (`Brai`).
+    pub const BRAILLE: Script = Script(sys::HB_SCRIPT_BRAILLE);
+    /// Canadian Syllabics (`Cans`).
+    pub const CANADIAN_SYLLABICS: Script = Script(sys::HB_SCRIPT_CANADIAN_SYLLABICS);
+    /// Cherokee (`Cher`).
+    pub const CHEROKEE: Script = Script(sys::HB_SCRIPT_CHEROKEE);
+    /// Ethiopic (`Ethi`).
+    pub const ETHIOPIC: Script = Script(sys::HB_SCRIPT_ETHIOPIC);
+    /// Khmer (`Khmr`).
+    pub const KHMER: Script = Script(sys::HB_SCRIPT_KHMER);
+    /// Mongolian (`Mong`).
+    pub const MONGOLIAN: Script = Script(sys::HB_SCRIPT_MONGOLIAN);
+    /// Myanmar (`Mymr`).
+    pub const MYANMAR: Script = Script(sys::HB_SCRIPT_MYANMAR);
+    /// Ogham (`Ogam`).
+    pub const OGHAM: Script = Script(sys::HB_SCRIPT_OGHAM);
+    /// Runic (`Runr`).
+    pub const RUNIC: Script = Script(sys::HB_SCRIPT_RUNIC);
+    /// Sinhala (`Sinh`).
+    pub const SINHALA: Script = Script(sys::HB_SCRIPT_SINHALA);
+    /// Syriac (`Syrc`).
+    pub const SYRIAC: Script = Script(sys::HB_SCRIPT_SYRIAC);
+    /// Thaana (`Thaa`).
+    pub const THAANA: Script = Script(sys::HB_SCRIPT_THAANA);
+    /// Yi (`Yiii`).
+    pub const YI: Script = Script(sys::HB_SCRIPT_YI);
+    /// Deseret (`Dsrt`).
+    pub const DESERET: Script = Script(sys::HB_SCRIPT_DESERET);
+    /// Gothic (`Goth`).
+    pub const GOTHIC: Script = Script(sys::HB_SCRIPT_GOTHIC);
+    /// Old Italic (`Ital`).
+    pub const OLD_ITALIC: Script = Script(sys::HB_SCRIPT_OLD_ITALIC);
+    /// Buhid (`Buhd`).
+    pub const BUHID: Script = Script(sys::HB_SCRIPT_BUHID);
+    /// Hanunoo (`Hano`).
+    pub const HANUNOO: Script = Script(sys::HB_SCRIPT_HANUNOO);
+    /// Tagalog (`Tglg`).
+    pub const TAGALOG: Script = Script(sys::HB_SCRIPT_TAGALOG);
+    /// Tagbanwa (`Tagb`).
+    pub const TAGBANWA: Script = Script(sys::HB_SCRIPT_TAGBANWA);
+    /// Cypriot (`Cprt`).
+    pub const CYPRIOT: Script = Script(sys::HB_SCRIPT_CYPRIOT);
+    /// Limbu (`Limb`).
+    pub const LIMBU: Script = Script(sys::HB_SCRIPT_LIMBU);
+    /// Linear B (`Linb`).
+    pub const LINEAR_B: Script = Script(sys::HB_SCRIPT_LINEAR_B);
+    /// Osmanya (`Osma`).
+    pub const OSMANYA: Script = Script(sys::HB_SCRIPT_OSMANYA);
+    /// Shavian (`Shaw`).
+    pub const SHAVIAN: Script = Script(sys::HB_SCRIPT_SHAVIAN);
+    /// Tai Le (`Tale`).
+    pub const TAI_LE: Script = Script(sys::HB_SCRIPT_TAI_LE);
+    /// Ugaritic (`Ugar`).
+    pub const UGARITIC: Script = Script(sys::HB_SCRIPT_UGARITIC);
+    /// Buginese (`Bugi`).
+    pub const BUGINESE: Script = Script(sys::HB_SCRIPT_BUGINESE);
+    /// Coptic (`Copt`).
+    pub const COPTIC: Script = Script(sys::HB_SCRIPT_COPTIC);
+    /// Glagolitic (`Glag`).
+    pub const GLAGOLITIC: Script = Script(sys::HB_SCRIPT_GLAGOLITIC);
+    /// Kharoshthi (`Khar`).
+    pub const KHAROSHTHI: Script = Script(sys::HB_SCRIPT_KHAROSHTHI);
+    /// New Tai Lue (`Talu`).
+    pub const NEW_TAI_LUE: Script = Script(sys::HB_SCRIPT_NEW_TAI_LUE);
+    /// Old Persian (`Xpeo`).
+    pub const OLD_PERSIAN: Script = Script(sys::HB_SCRIPT_OLD_PERSIAN);
+    /// Syloti Nagri (`Sylo`).
+    pub const SYLOTI_NAGRI: Script = Script(sys::HB_SCRIPT_SYLOTI_NAGRI);
+    /// Tifinagh (`Tfng`).
+    pub const TIFINAGH: Script = Script(sys::HB_SCRIPT_TIFINAGH);
+    /// Balinese (`Bali`).
+    pub const BALINESE: Script = Script(sys::HB_SCRIPT_BALINESE);
+    /// Cuneiform (`Xsux`).
+    pub const CUNEIFORM: Script = Script(sys::HB_SCRIPT_CUNEIFORM);
+    /// Nko (`Nkoo`).
+    pub const NKO: Script = Script(sys::HB_SCRIPT_NKO);
+    /// Phags Pa (`Phag`).
+    pub const PHAGS_PA: Script = Script(sys::HB_SCRIPT_PHAGS_PA);
+    /// Phoenician (`Phnx`).
+    pub const PHOENICIAN: Script = Script(sys::HB_SCRIPT_PHOENICIAN);
+    /// Carian (`Cari`).
+    pub const CARIAN: Script = Script(sys::HB_SCRIPT_CARIAN);
+    /// Cham (`Cham`).
+    pub const CHAM: Script = Script(sys::HB_SCRIPT_CHAM);
+    /// Kayah Li (`Kali`).
+    pub const KAYAH_LI: Script = Script(sys::HB_SCRIPT_KAYAH_LI);
+    /// Lepcha (`Lepc`).
+    pub const LEPCHA: Script = Script(sys::HB_SCRIPT_LEPCHA);
+    /// Lycian (`Lyci`).
+    pub const LYCIAN: Script = Script(sys::HB_SCRIPT_LYCIAN);
+    /// Lydian (`Lydi`).
+    pub const LYDIAN: Script = Script(sys::HB_SCRIPT_LYDIAN);
+    /// Ol Chiki (`Olck`).
+    pub const OL_CHIKI: Script = Script(sys::HB_SCRIPT_OL_CHIKI);
+    /// Rejang (`Rjng`).
+    pub const REJANG: Script = Script(sys::HB_SCRIPT_REJANG);
+    /// Saurashtra (`Saur`).
+    pub const SAURASHTRA: Script = Script(sys::HB_SCRIPT_SAURASHTRA);
+    /// Sundanese (`Sund`).
+    pub const SUNDANESE: Script = Script(sys::HB_SCRIPT_SUNDANESE);
+    /// Vai (`Vaii`).
+    pub const VAI: Script = Script(sys::HB_SCRIPT_VAI);
+    /// Avestan (`Avst`).
+    pub const AVESTAN: Script = Script(sys::HB_SCRIPT_AVESTAN);
+    /// Bamum (`Bamu`).
+    pub const BAMUM: Script = Script(sys::HB_SCRIPT_BAMUM);
+    /// Egyptian Hieroglyphs (`Egyp`).
+    pub const EGYPTIAN_HIEROGLYPHS: Script = Script(sys::HB_SCRIPT_EGYPTIAN_HIEROGLYPHS);
+    /// Imperial Aramaic (`Armi`).
+    pub const IMPERIAL_ARAMAIC: Script = Script(sys::HB_SCRIPT_IMPERIAL_ARAMAIC);
+    /// Inscriptional Pahlavi (`Phli`).
+    pub const INSCRIPTIONAL_PAHLAVI: Script = Script(sys::HB_SCRIPT_INSCRIPTIONAL_PAHLAVI);
+    /// Inscriptional Parthian (`Prti`).
+    pub const INSCRIPTIONAL_PARTHIAN: Script = Script(sys::HB_SCRIPT_INSCRIPTIONAL_PARTHIAN);
+    /// Javanese (`Java`).
+    pub const JAVANESE: Script = Script(sys::HB_SCRIPT_JAVANESE);
+    /// Kaithi (`Kthi`).
+    pub const KAITHI: Script = Script(sys::HB_SCRIPT_KAITHI);
+    /// Lisu (`Lisu`).
+    pub const LISU: Script = Script(sys::HB_SCRIPT_LISU);
+    /// Meetei Mayek (`Mtei`).
+    pub const MEETEI_MAYEK: Script = Script(sys::HB_SCRIPT_MEETEI_MAYEK);
+    /// Old South Arabian (`Sarb`).
+    pub const OLD_SOUTH_ARABIAN: Script = Script(sys::HB_SCRIPT_OLD_SOUTH_ARABIAN);
+    /// Old Turkic (`Orkh`).
+    pub const OLD_TURKIC: Script = Script(sys::HB_SCRIPT_OLD_TURKIC);
+    /// Samaritan (`Samr`).
+    pub const SAMARITAN: Script = Script(sys::HB_SCRIPT_SAMARITAN);
+    /// Tai Tham (`Lana`).
+    pub const TAI_THAM: Script = Script(sys::HB_SCRIPT_TAI_THAM);
+    /// Tai Viet (`Tavt`).
+    pub const TAI_VIET: Script = Script(sys::HB_SCRIPT_TAI_VIET);
+    /// Batak (`Batk`).
+    pub const BATAK: Script = Script(sys::HB_SCRIPT_BATAK);
+    /// Brahmi (`Brah`).
+    pub const BRAHMI: Script = Script(sys::HB_SCRIPT_BRAHMI);
+    /// Mandaic (`Mand`).
+    pub const MANDAIC: Script = Script(sys::HB_SCRIPT_MANDAIC);
+    /// Chakma (`Cakm`).
+    pub const CHAKMA: Script = Script(sys::HB_SCRIPT_CHAKMA);
+    /// Meroitic Cursive (`Merc`).
+    pub const MEROITIC_CURSIVE: Script = Script(sys::HB_SCRIPT_MEROITIC_CURSIVE);
+    /// Meroitic Hieroglyphs (`Mero`).
+    pub const MEROITIC_HIEROGLYPHS: Script = Script(sys::HB_SCRIPT_MEROITIC_HIEROGLYPHS);
+    /// Miao (`Plrd`).
+    pub const MIAO: Script = Script(sys::HB_SCRIPT_MIAO);
+    /// Sharada (`Shrd`).
+    pub const SHARADA: Script = Script(sys::HB_SCRIPT_SHARADA);
+    /// Sora Sompeng (`Sora`).
+    pub const SORA_SOMPENG: Script = Script(sys::HB_SCRIPT_SORA_SOMPENG);
+    /// Takri (`Takr`).
+    pub const TAKRI: Script = Script(sys::HB_SCRIPT_TAKRI);
+    /// Bassa Vah (`Bass`).
+    pub const BASSA_VAH: Script = Script(sys::HB_SCRIPT_BASSA_VAH);
+    /// Caucasian Albanian (`Aghb`).
+    pub const CAUCASIAN_ALBANIAN: Script = Script(sys::HB_SCRIPT_CAUCASIAN_ALBANIAN);
+    /// Duployan (`Dupl`).
+    pub const DUPLOYAN: Script = Script(sys::HB_SCRIPT_DUPLOYAN);
+    /// Elbasan (`Elba`).
+    pub const ELBASAN: Script = Script(sys::HB_SCRIPT_ELBASAN);
+    /// Grantha (`Gran`).
+    pub const GRANTHA: Script = Script(sys::HB_SCRIPT_GRANTHA);
+    /// Khojki (`Khoj`).
+    pub const KHOJKI: Script = Script(sys::HB_SCRIPT_KHOJKI);
+    /// Khudawadi (`Sind`).
+    pub const KHUDAWADI: Script = Script(sys::HB_SCRIPT_KHUDAWADI);
+    /// Linear A (`Lina`).
+    pub const LINEAR_A: Script = Script(sys::HB_SCRIPT_LINEAR_A);
+    /// Mahajani (`Mahj`).
+    pub const MAHAJANI: Script = Script(sys::HB_SCRIPT_MAHAJANI);
+    /// Manichaean (`Mani`).
+    pub const MANICHAEAN: Script = Script(sys::HB_SCRIPT_MANICHAEAN);
+    /// Mende Kikakui (`Mend`).
+    pub const MENDE_KIKAKUI: Script = Script(sys::HB_SCRIPT_MENDE_KIKAKUI);
+    /// Modi (`Modi`).
+    pub const MODI: Script = Script(sys::HB_SCRIPT_MODI);
+    /// Mro (`Mroo`).
+    pub const MRO: Script = Script(sys::HB_SCRIPT_MRO);
+    /// Nabataean (`Nbat`).
+    pub const NABATAEAN: Script = Script(sys::HB_SCRIPT_NABATAEAN);
+    /// Old North Arabian (`Narb`).
+    pub const OLD_NORTH_ARABIAN: Script = Script(sys::HB_SCRIPT_OLD_NORTH_ARABIAN);
+    /// Old Permic (`Perm`).
+    pub const OLD_PERMIC: Script = Script(sys::HB_SCRIPT_OLD_PERMIC);
+    /// Pahawh Hmong (`Hmng`).
+    pub const PAHAWH_HMONG: Script = Script(sys::HB_SCRIPT_PAHAWH_HMONG);
+    /// Palmyrene (`Palm`).
+    pub const PALMYRENE: Script = Script(sys::HB_SCRIPT_PALMYRENE);
+    /// Pau Cin Hau (`Pauc`).
+    pub const PAU_CIN_HAU: Script = Script(sys::HB_SCRIPT_PAU_CIN_HAU);
+    /// Psalter Pahlavi (`Phlp`).
+    pub const PSALTER_PAHLAVI: Script = Script(sys::HB_SCRIPT_PSALTER_PAHLAVI);
+    /// Siddham (`Sidd`).
+    pub const SIDDHAM: Script = Script(sys::HB_SCRIPT_SIDDHAM);
+    /// Tirhuta (`Tirh`).
+    pub const TIRHUTA: Script = Script(sys::HB_SCRIPT_TIRHUTA);
+    /// Warang Citi (`Wara`).
+    pub const WARANG_CITI: Script = Script(sys::HB_SCRIPT_WARANG_CITI);
+    /// Ahom (`Ahom`).
+    pub const AHOM: Script = Script(sys::HB_SCRIPT_AHOM);
+    /// Anatolian Hieroglyphs (`Hluw`).
+    pub const ANATOLIAN_HIEROGLYPHS: Script = Script(sys::HB_SCRIPT_ANATOLIAN_HIEROGLYPHS);
+    /// Hatran (`Hatr`).
+    pub const HATRAN: Script = Script(sys::HB_SCRIPT_HATRAN);
+    /// Multani (`Mult`).
+    pub const MULTANI: Script = Script(sys::HB_SCRIPT_MULTANI);
+    /// Old Hungarian (`Hung`).
+    pub const OLD_HUNGARIAN: Script = Script(sys::HB_SCRIPT_OLD_HUNGARIAN);
+    /// Signwriting (`Sgnw`).
+    pub const SIGNWRITING: Script = Script(sys::HB_SCRIPT_SIGNWRITING);
+    /// Adlam (`Adlm`).
+    pub const ADLAM: Script = Script(sys::HB_SCRIPT_ADLAM);
+    /// Bhaiksuki (`Bhks`).
+    pub const BHAIKSUKI: Script = Script(sys::HB_SCRIPT_BHAIKSUKI);
+    /// Marchen (`Marc`).
+    pub const MARCHEN: Script = Script(sys::HB_SCRIPT_MARCHEN);
+    /// Osage (`Osge`).
+    pub const OSAGE: Script = Script(sys::HB_SCRIPT_OSAGE);
+    /// Tangut (`Tang`).
+    pub const TANGUT: Script = Script(sys::HB_SCRIPT_TANGUT);
+    /// Newa (`Newa`).
+    pub const NEWA: Script = Script(sys::HB_SCRIPT_NEWA);
+    /// Masaram Gondi (`Gonm`).
+    pub const MASARAM_GONDI: Script = Script(sys::HB_SCRIPT_MASARAM_GONDI);
+    /// Nushu (`Nshu`).
+    pub const NUSHU: Script = Script(sys::HB_SCRIPT_NUSHU);
+    /// Soyombo (`Soyo`).
+    pub const SOYOMBO: Script = Script(sys::HB_SCRIPT_SOYOMBO);
+    /// Zanabazar Square (`Zanb`).
+    pub const ZANABAZAR_SQUARE: Script = Script(sys::HB_SCRIPT_ZANABAZAR_SQUARE);
+    /// Dogra (`Dogr`).
+    pub const DOGRA: Script = Script(sys::HB_SCRIPT_DOGRA);
+    /// Gunjala Gondi (`Gong`).
+    pub const GUNJALA_GONDI: Script = Script(sys::HB_SCRIPT_GUNJALA_GONDI);
+    /// Hanifi Rohingya (`Rohg`).
+    pub const HANIFI_ROHINGYA: Script = Script(sys::HB_SCRIPT_HANIFI_ROHINGYA);
+    /// Makasar (`Maka`).
+    pub const MAKASAR: Script = Script(sys::HB_SCRIPT_MAKASAR);
+    /// Medefaidrin (`Medf`).
+    pub const MEDEFAIDRIN: Script = Script(sys::HB_SCRIPT_MEDEFAIDRIN);
+    /// Old Sogdian (`Sogo`).
+    pub const OLD_SOGDIAN: Script = Script(sys::HB_SCRIPT_OLD_SOGDIAN);
+    /// Sogdian (`Sogd`).
+    pub const SOGDIAN: Script = Script(sys::HB_SCRIPT_SOGDIAN);
+    /// Elymaic (`Elym`).
+    pub const ELYMAIC: Script = Script(sys::HB_SCRIPT_ELYMAIC);
+    /// Nandinagari (`Nand`).
+    pub const NANDINAGARI: Script = Script(sys::HB_SCRIPT_NANDINAGARI);
+    /// Nyiakeng Puachue Hmong (`Hmnp`).
+    pub const NYIAKENG_PUACHUE_HMONG: Script = Script(sys::HB_SCRIPT_NYIAKENG_PUACHUE_HMONG);
+    /// Wancho (`Wcho`).
+    pub const WANCHO: Script = Script(sys::HB_SCRIPT_WANCHO);
+    /// Chorasmian (`Chrs`).
+    pub const CHORASMIAN: Script = Script(sys::HB_SCRIPT_CHORASMIAN);
+    /// Dives Akuru (`Diak`).
+    pub const DIVES_AKURU: Script = Script(sys::HB_SCRIPT_DIVES_AKURU);
+    /// Khitan Small Script (`Kits`).
+    pub const KHITAN_SMALL_SCRIPT: Script = Script(sys::HB_SCRIPT_KHITAN_SMALL_SCRIPT);
+    /// Yezidi (`Yezi`).
+    pub const YEZIDI: Script = Script(sys::HB_SCRIPT_YEZIDI);
+    /// Cypro Minoan (`Cpmn`).
+    pub const CYPRO_MINOAN: Script = Script(sys::HB_SCRIPT_CYPRO_MINOAN);
+    /// Old Uyghur (`Ougr`).
+    pub const OLD_UYGHUR: Script = Script(sys::HB_SCRIPT_OLD_UYGHUR);
+    /// Tangsa (`Tnsa`).
+    pub const TANGSA: Script = Script(sys::HB_SCRIPT_TANGSA);
+    /// Toto (`Toto`).
+    pub const TOTO: Script = Script(sys::HB_SCRIPT_TOTO);
+    /// Vithkuqi (`Vith`).
+    pub const VITHKUQI: Script = Script(sys::HB_SCRIPT_VITHKUQI);
+    /// Math (`Zmth`).
+    pub const MATH: Script = Script(sys::HB_SCRIPT_MATH);
+
+    /// Converts an ISO 15924 script tag to a `Script`.
+    ///
+    /// Returns [`Script::INVALID`] for [`Tag::NONE`], and
+    /// [`Script::UNKNOWN`] for tags that are not a script code.
+    ///
+    /// ```
+    /// # use harfbuzz::{Script, Tag};
+    /// let tag: Tag = "Grek".parse().unwrap();
+    /// assert_eq!(Script::from_iso15924_tag(tag), Script::GREEK);
+    /// ```
+    pub fn from_iso15924_tag(tag: Tag) -> Script {
+        Script(unsafe { sys::hb_script_from_iso15924_tag(tag.into()) })
+    }
+
+    /// Returns the ISO 15924 tag of the script.
+    pub fn to_iso15924_tag(self) -> Tag {
+        unsafe { sys::hb_script_to_iso15924_tag(self.0) }.into()
+    }
+
+    /// Returns the natural horizontal direction of text in the script.
+    ///
+    /// Returns [`Direction::Invalid`] for scripts that can be written in
+    /// either horizontal direction, such as [`Script::COMMON`].
+    ///
+    /// ```
+    /// # use harfbuzz::{Direction, Script};
+    /// assert_eq!(Script::LATIN.horizontal_direction(), Direction::LTR);
+    /// assert_eq!(Script::HEBREW.horizontal_direction(), Direction::RTL);
+    /// ```
+    pub fn horizontal_direction(self) -> Direction {
+        unsafe { sys::hb_script_get_horizontal_direction(self.0) }.into()
+    }
+
+    /// Returns true unless the script is [`Script::INVALID`].
+    pub fn is_valid(self) -> bool {
+        self != Script::INVALID
+    }
+}
+
+impl Default for Script {
+    fn default() -> Script {
+        Script::INVALID
+    }
+}
+
+#[doc(hidden)]
+impl From<sys::hb_script_t> for Script {
+    fn from(script: sys::hb_script_t) -> Self {
+        Script(script)
+    }
+}
+
+#[doc(hidden)]
+impl From<Script> for sys::hb_script_t {
+    fn from(script: Script) -> Self {
+        script.0
+    }
+}
+
+/// Parses an ISO 15924 script code, such as `Latn`.
+///
+/// Only the first four characters are considered, and case is ignored.
+/// Codes that are well formed but unknown to HarfBuzz are accepted as is.
+impl FromStr for Script {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let script = unsafe {
+            sys::hb_script_from_string(
+                s.as_ptr() as *const std::os::raw::c_char,
+                s.len() as std::os::raw::c_int,
+            )
+        };
+        if script == sys::HB_SCRIPT_INVALID {
+            Err(())
+        } else {
+            Ok(Script(script))
+        }
+    }
+}
+
+impl std::fmt::Display for Script {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.to_iso15924_tag(), fmt)
+    }
+}
+
+impl std::fmt::Debug for Script {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_tuple("Script")
+            .field(&format_args!("{}", self))
+            .finish()
+    }
+}
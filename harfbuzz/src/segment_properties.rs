@@ -0,0 +1,167 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::hash::{Hash, Hasher};
+
+use crate::{Direction, Language, Script};
+
+/// The direction, script and language of a run of text.
+///
+/// These are the properties that, together with the font and the
+/// features, decide how a segment of text gets shaped. Two segments
+/// with equal properties can share a shape plan, which makes
+/// `SegmentProperties` usable as a cache key:
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use harfbuzz::{Buffer, Direction, Language, Script, SegmentProperties};
+/// let props = SegmentProperties::new(
+///     Direction::LTR,
+///     Script::LATIN,
+///     Language::from_string("en"),
+/// );
+///
+/// let mut b = Buffer::with("Hello");
+/// b.guess_segment_properties();
+/// b.set_language(Language::from_string("en"));
+///
+/// let mut cache = HashMap::new();
+/// cache.insert(props, "shaped run");
+/// assert_eq!(cache.get(&b.segment_properties()), Some(&"shaped run"));
+/// ```
+///
+/// This maps to the [`hb_segment_properties_t`] from [`harfbuzz-sys`].
+///
+/// [`hb_segment_properties_t`]: ../harfbuzz_sys/struct.hb_segment_properties_t.html
+/// [`harfbuzz-sys`]: ../harfbuzz_sys/index.html
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct SegmentProperties(sys::hb_segment_properties_t);
+
+impl SegmentProperties {
+    /// Create segment properties from their parts.
+    pub fn new(direction: Direction, script: Script, language: Language) -> Self {
+        let mut props = SegmentProperties::default();
+        props.set_direction(direction);
+        props.set_script(script);
+        props.set_language(language);
+        props
+    }
+
+    /// The text flow direction.
+    pub fn direction(&self) -> Direction {
+        self.0.direction.into()
+    }
+
+    /// Set the text flow direction.
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.0.direction = direction.into();
+    }
+
+    /// The script.
+    pub fn script(&self) -> Script {
+        self.0.script.into()
+    }
+
+    /// Set the script.
+    pub fn set_script(&mut self, script: Script) {
+        self.0.script = script.into();
+    }
+
+    /// The language.
+    pub fn language(&self) -> Language {
+        unsafe { Language::from_raw(self.0.language) }
+    }
+
+    /// Set the language.
+    pub fn set_language(&mut self, language: Language) {
+        self.0.language = language.as_raw();
+    }
+
+    /// Fill in the properties of `self` that are unset with those of `src`.
+    ///
+    /// Properties are considered in the order direction, script, language,
+    /// and filling stops at the first one that is set in both and differs,
+    /// so that e.g. a language is never borrowed from a segment in a
+    /// different script.
+    ///
+    /// ```
+    /// # use harfbuzz::{Direction, Language, Script, SegmentProperties};
+    /// let defaults = SegmentProperties::new(
+    ///     Direction::RTL,
+    ///     Script::ARABIC,
+    ///     Language::from_string("ar"),
+    /// );
+    ///
+    /// let mut props = SegmentProperties::default();
+    /// props.set_script(Script::ARABIC);
+    /// props.overlay(&defaults);
+    /// assert_eq!(props, defaults);
+    ///
+    /// let mut props = SegmentProperties::default();
+    /// props.set_script(Script::SYRIAC);
+    /// props.overlay(&defaults);
+    /// assert_eq!(props.direction(), Direction::RTL);
+    /// assert!(!props.language().is_valid());
+    /// ```
+    pub fn overlay(&mut self, src: &SegmentProperties) {
+        unsafe { sys::hb_segment_properties_overlay(&mut self.0, &src.0) }
+    }
+}
+
+impl Default for SegmentProperties {
+    /// Segment properties with every property unset.
+    fn default() -> Self {
+        SegmentProperties(sys::hb_segment_properties_t {
+            direction: sys::HB_DIRECTION_INVALID,
+            script: sys::HB_SCRIPT_INVALID,
+            language: std::ptr::null(),
+            reserved1: std::ptr::null_mut(),
+            reserved2: std::ptr::null_mut(),
+        })
+    }
+}
+
+impl PartialEq for SegmentProperties {
+    fn eq(&self, other: &SegmentProperties) -> bool {
+        unsafe { sys::hb_segment_properties_equal(&self.0, &other.0) != 0 }
+    }
+}
+
+impl Eq for SegmentProperties {}
+
+impl Hash for SegmentProperties {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u32(unsafe { sys::hb_segment_properties_hash(&self.0) });
+    }
+}
+
+impl std::fmt::Debug for SegmentProperties {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("SegmentProperties")
+            .field("direction", &self.direction())
+            .field("script", &self.script())
+            .field("language", &self.language())
+            .finish()
+    }
+}
+
+#[doc(hidden)]
+impl From<sys::hb_segment_properties_t> for SegmentProperties {
+    fn from(props: sys::hb_segment_properties_t) -> Self {
+        SegmentProperties(props)
+    }
+}
+
+#[doc(hidden)]
+impl From<SegmentProperties> for sys::hb_segment_properties_t {
+    fn from(props: SegmentProperties) -> Self {
+        props.0
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// The text format used to serialize the contents of a
+/// [`Buffer`](crate::Buffer).
+///
+/// Both formats are the ones used by the `hb-shape` and `hb-view`
+/// utilities.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SerializeFormat {
+    /// A human-readable, plain text format, e.g.
+    /// `[uni0041=0+1366|uni0042=1+1255]`.
+    Text,
+    /// A machine-readable JSON format.
+    Json,
+}
+
+impl From<SerializeFormat> for sys::hb_buffer_serialize_format_t {
+    fn from(s: SerializeFormat) -> Self {
+        match s {
+            SerializeFormat::Text => sys::HB_BUFFER_SERIALIZE_FORMAT_TEXT,
+            SerializeFormat::Json => sys::HB_BUFFER_SERIALIZE_FORMAT_JSON,
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags that choose what is included when serializing a
+    /// [`Buffer`](crate::Buffer).
+    #[repr(transparent)]
+    pub struct SerializeFlags: u32 {
+        /// Serialize glyph names, clusters and positions.
+        const DEFAULT = sys::HB_BUFFER_SERIALIZE_FLAG_DEFAULT;
+        /// Do not serialize glyph cluster.
+        const NO_CLUSTERS = sys::HB_BUFFER_SERIALIZE_FLAG_NO_CLUSTERS;
+        /// Do not serialize glyph position information.
+        const NO_POSITIONS = sys::HB_BUFFER_SERIALIZE_FLAG_NO_POSITIONS;
+        /// Do not serialize glyph names, use glyph IDs instead.
+        const NO_GLYPH_NAMES = sys::HB_BUFFER_SERIALIZE_FLAG_NO_GLYPH_NAMES;
+        /// Serialize glyph extents.
+        const GLYPH_EXTENTS = sys::HB_BUFFER_SERIALIZE_FLAG_GLYPH_EXTENTS;
+        /// Serialize glyph flags.
+        const GLYPH_FLAGS = sys::HB_BUFFER_SERIALIZE_FLAG_GLYPH_FLAGS;
+        /// Do not serialize glyph advances, only offsets.
+        const NO_ADVANCES = sys::HB_BUFFER_SERIALIZE_FLAG_NO_ADVANCES;
+    }
+}
+
+impl Default for SerializeFlags {
+    fn default() -> SerializeFlags {
+        SerializeFlags::DEFAULT
+    }
+}
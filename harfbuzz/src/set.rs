@@ -0,0 +1,282 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+
+use crate::Error;
+
+/// The value HarfBuzz uses for "no value", `HB_SET_VALUE_INVALID`.
+const INVALID: u32 = u32::MAX;
+
+/// A set of `u32` values, such as codepoints, glyph IDs or lookup
+/// indices.
+///
+/// Sets are stored as sparse bitmaps, so large ranges are cheap. Any
+/// value except `u32::MAX` can be stored.
+///
+/// ```
+/// # use harfbuzz::Set;
+/// let mut set: Set = [1, 2, 3, 10].into_iter().collect();
+/// set.insert_range(20..=29);
+/// set.remove(2);
+/// assert!(set.contains(1) && !set.contains(2));
+/// assert_eq!(set.len(), 13);
+/// assert_eq!((set.min(), set.max()), (Some(1), Some(29)));
+/// assert_eq!(set.ranges().collect::<Vec<_>>(), [1..=1, 3..=3, 10..=10, 20..=29]);
+/// ```
+///
+/// A set that failed to allocate memory ignores further changes;
+/// [`check_allocation`](#method.check_allocation) tells whether that
+/// happened.
+///
+/// This maps to the [`hb_set_t`] from [`harfbuzz-sys`].
+///
+/// [`hb_set_t`]: ../harfbuzz_sys/struct.hb_set_t.html
+/// [`harfbuzz-sys`]: ../harfbuzz_sys/index.html
+pub struct Set {
+    raw: *mut sys::hb_set_t,
+}
+
+impl Set {
+    /// Create an empty set.
+    pub fn new() -> Set {
+        unsafe { Set::from_raw(sys::hb_set_create()) }
+    }
+
+    /// Whether all allocations of the set succeeded.
+    ///
+    /// Returns [`Error::NoMemory`] if one failed, after which the set is
+    /// left unchanged by any operation that needs to allocate.
+    pub fn check_allocation(&self) -> Result<(), Error> {
+        if unsafe { sys::hb_set_allocation_successful(self.raw) } != 0 {
+            Ok(())
+        } else {
+            Err(Error::NoMemory)
+        }
+    }
+
+    /// The number of values in the set.
+    pub fn len(&self) -> usize {
+        unsafe { sys::hb_set_get_population(self.raw) as usize }
+    }
+
+    /// Whether the set holds no values.
+    pub fn is_empty(&self) -> bool {
+        unsafe { sys::hb_set_is_empty(self.raw) != 0 }
+    }
+
+    /// Whether `value` is in the set.
+    pub fn contains(&self, value: u32) -> bool {
+        unsafe { sys::hb_set_has(self.raw, value) != 0 }
+    }
+
+    /// Add `value` to the set. `u32::MAX` is ignored.
+    pub fn insert(&mut self, value: u32) {
+        unsafe { sys::hb_set_add(self.raw, value) }
+    }
+
+    /// Add all values in `range` to the set.
+    pub fn insert_range(&mut self, range: RangeInclusive<u32>) {
+        unsafe { sys::hb_set_add_range(self.raw, *range.start(), *range.end()) }
+    }
+
+    /// Remove `value` from the set.
+    pub fn remove(&mut self, value: u32) {
+        unsafe { sys::hb_set_del(self.raw, value) }
+    }
+
+    /// Remove all values in `range` from the set.
+    pub fn remove_range(&mut self, range: RangeInclusive<u32>) {
+        unsafe { sys::hb_set_del_range(self.raw, *range.start(), *range.end()) }
+    }
+
+    /// Remove all values from the set.
+    pub fn clear(&mut self) {
+        unsafe { sys::hb_set_clear(self.raw) }
+    }
+
+    /// Add the values of `other` to the set.
+    pub fn union(&mut self, other: &Set) {
+        unsafe { sys::hb_set_union(self.raw, other.raw) }
+    }
+
+    /// Keep only the values that are also in `other`.
+    pub fn intersect(&mut self, other: &Set) {
+        unsafe { sys::hb_set_intersect(self.raw, other.raw) }
+    }
+
+    /// Remove the values that are in `other`.
+    pub fn subtract(&mut self, other: &Set) {
+        unsafe { sys::hb_set_subtract(self.raw, other.raw) }
+    }
+
+    /// Keep only the values that are in exactly one of the set and
+    /// `other`.
+    pub fn symmetric_difference(&mut self, other: &Set) {
+        unsafe { sys::hb_set_symmetric_difference(self.raw, other.raw) }
+    }
+
+    /// Replace the set with its complement, i.e. all values that were
+    /// not in it.
+    ///
+    /// ```
+    /// # use harfbuzz::Set;
+    /// let mut set: Set = [0, 1, 5].into_iter().collect();
+    /// set.invert();
+    /// assert!(!set.contains(1) && set.contains(2));
+    /// assert_eq!(set.min(), Some(2));
+    /// assert_eq!(set.len(), u32::MAX as usize - 3);
+    /// ```
+    pub fn invert(&mut self) {
+        unsafe { sys::hb_set_invert(self.raw) }
+    }
+
+    /// Whether all values of the set are also in `larger`.
+    pub fn is_subset(&self, larger: &Set) -> bool {
+        unsafe { sys::hb_set_is_subset(self.raw, larger.raw) != 0 }
+    }
+
+    /// The smallest value in the set.
+    pub fn min(&self) -> Option<u32> {
+        Some(unsafe { sys::hb_set_get_min(self.raw) }).filter(|&value| value != INVALID)
+    }
+
+    /// The largest value in the set.
+    pub fn max(&self) -> Option<u32> {
+        Some(unsafe { sys::hb_set_get_max(self.raw) }).filter(|&value| value != INVALID)
+    }
+
+    /// The values in the set, in increasing order.
+    ///
+    /// Values are fetched from HarfBuzz many at a time.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        let mut page = [0; 64];
+        let (mut pos, mut len) = (0, 0);
+        let mut last = INVALID;
+        std::iter::from_fn(move || {
+            if pos == len {
+                if len < page.len() && last != INVALID {
+                    return None;
+                }
+                len = unsafe {
+                    sys::hb_set_next_many(self.raw, last, page.as_mut_ptr(), page.len() as u32)
+                } as usize;
+                pos = 0;
+                if len == 0 {
+                    return None;
+                }
+                last = page[len - 1];
+            }
+            pos += 1;
+            Some(page[pos - 1])
+        })
+    }
+
+    /// The runs of consecutive values in the set, in increasing order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<u32>> + '_ {
+        let mut last = INVALID;
+        std::iter::from_fn(move || {
+            let mut first = 0;
+            if unsafe { sys::hb_set_next_range(self.raw, &mut first, &mut last) } != 0 {
+                Some(first..=last)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Wrap a raw `hb_set_t`, taking over its reference.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid pointer to an `hb_set_t`.
+    pub unsafe fn from_raw(raw: *mut sys::hb_set_t) -> Set {
+        Set { raw }
+    }
+
+    /// Give up the set, returning its raw `hb_set_t` with its reference.
+    pub fn into_raw(self) -> *mut sys::hb_set_t {
+        let raw = self.raw;
+        std::mem::forget(self);
+        raw
+    }
+
+    /// The underlying `hb_set_t`.
+    pub fn as_ptr(&self) -> *const sys::hb_set_t {
+        self.raw
+    }
+
+    /// The underlying `hb_set_t`, for changing it.
+    pub fn as_mut_ptr(&mut self) -> *mut sys::hb_set_t {
+        self.raw
+    }
+}
+
+impl Default for Set {
+    fn default() -> Set {
+        Set::new()
+    }
+}
+
+impl Clone for Set {
+    /// Copy the values into a new set.
+    fn clone(&self) -> Set {
+        unsafe {
+            let raw = sys::hb_set_copy(self.raw);
+            if raw.is_null() {
+                Set::from_raw(sys::hb_set_get_empty())
+            } else {
+                Set::from_raw(raw)
+            }
+        }
+    }
+}
+
+impl Drop for Set {
+    fn drop(&mut self) {
+        unsafe { sys::hb_set_destroy(self.raw) }
+    }
+}
+
+impl PartialEq for Set {
+    fn eq(&self, other: &Set) -> bool {
+        unsafe { sys::hb_set_is_equal(self.raw, other.raw) != 0 }
+    }
+}
+
+impl Eq for Set {}
+
+impl Hash for Set {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u32(unsafe { sys::hb_set_hash(self.raw) });
+    }
+}
+
+impl std::fmt::Debug for Set {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_set().entries(self.ranges()).finish()
+    }
+}
+
+impl FromIterator<u32> for Set {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Set {
+        let mut set = Set::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<u32> for Set {
+    fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
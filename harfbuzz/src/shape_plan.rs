@@ -0,0 +1,236 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::BTreeSet;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+use crate::{
+    Buffer, BufferContentType, Error, Face, Feature, Font, LayoutTable, SegmentProperties, Set,
+};
+
+/// A NULL-terminated list of shaper names, as taken by `hb_shape_full` and
+/// the shape plan constructors.
+pub(crate) struct ShaperList {
+    _names: Vec<CString>,
+    list: Option<Vec<*const c_char>>,
+}
+
+impl ShaperList {
+    /// Names that contain a NUL byte can not be shapers and are skipped.
+    pub(crate) fn new(shapers: Option<&[&str]>) -> Self {
+        let names: Vec<CString> = shapers
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|&name| CString::new(name).ok())
+            .collect();
+        let list = shapers.map(|_| {
+            let mut list: Vec<*const c_char> = names.iter().map(|name| name.as_ptr()).collect();
+            list.push(std::ptr::null());
+            list
+        });
+        ShaperList {
+            _names: names,
+            list,
+        }
+    }
+
+    /// A null pointer if no list was given, which means all shapers.
+    pub(crate) fn as_ptr(&self) -> *const *const c_char {
+        self.list
+            .as_ref()
+            .map_or(std::ptr::null(), |list| list.as_ptr())
+    }
+}
+
+/// A plan for shaping text with a face, segment properties and features.
+///
+/// Creating a plan decides which lookups to apply, and is the expensive
+/// part of shaping short runs. A plan can be executed on any number of
+/// buffers with the same segment properties, using fonts of the face it
+/// was created for.
+///
+/// Plans are cached on the face, so creating a plan for the same
+/// arguments again is cheap and returns the same plan. Cloning a
+/// `ShapePlan` only adds a reference to it. A plan keeps a reference to
+/// its face, so the face lives at least as long as the plan.
+///
+/// ```
+/// # use harfbuzz::{Blob, Buffer, Direction, Face, Font, Language, Script, SegmentProperties, ShapePlan};
+/// # let mut face = Face::new(&Blob::new_read_only(&[]), 0);
+/// # let font = Font::new(&mut face);
+/// let props = SegmentProperties::new(Direction::LTR, Script::LATIN, Language::from_string("en"));
+/// let plan = ShapePlan::new(&face, &props, &[], &[], None);
+/// assert_eq!(plan.shaper(), "ot");
+///
+/// for word in ["Hello", "world"] {
+///     let mut b = Buffer::with(word);
+///     b.set_segment_properties(&props);
+///     plan.execute(&font, &mut b, &[]).unwrap();
+///     assert_eq!(b.len(), 5);
+/// }
+/// ```
+///
+/// This maps to the [`hb_shape_plan_t`] from [`harfbuzz-sys`].
+///
+/// [`hb_shape_plan_t`]: ../harfbuzz_sys/struct.hb_shape_plan_t.html
+/// [`harfbuzz-sys`]: ../harfbuzz_sys/index.html
+pub struct ShapePlan {
+    raw: *mut sys::hb_shape_plan_t,
+    face: Face,
+    props: SegmentProperties,
+}
+
+impl ShapePlan {
+    /// Create a plan, or get it from the cache of `face`.
+    ///
+    /// `coords` are the normalized variation coordinates of the fonts the
+    /// plan will be used with, in 2.14 fixed point, and select the
+    /// feature variations to apply. `shapers` are the names of the
+    /// shapers to try, as in [`shape_full`](crate::shape_full).
+    ///
+    /// The plan may outlive `face`:
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Direction, Face, Language, Script, SegmentProperties, ShapePlan};
+    /// let props = SegmentProperties::new(Direction::LTR, Script::LATIN, Language::from_string("en"));
+    /// let plan = {
+    ///     let face = Face::new(&Blob::new_read_only(&[]), 0);
+    ///     ShapePlan::new(&face, &props, &[], &[], None)
+    /// };
+    /// assert!(plan.collect_lookups(harfbuzz::LayoutTable::Gsub).is_empty());
+    /// ```
+    pub fn new(
+        face: &Face,
+        props: &SegmentProperties,
+        features: &[Feature],
+        coords: &[i32],
+        shapers: Option<&[&str]>,
+    ) -> ShapePlan {
+        let shapers = ShaperList::new(shapers);
+        let props: sys::hb_segment_properties_t = (*props).into();
+        let raw = unsafe {
+            sys::hb_shape_plan_create_cached2(
+                face.as_ptr() as *mut sys::hb_face_t,
+                &props,
+                features.as_ptr() as *const sys::hb_feature_t,
+                features.len().try_into().unwrap(),
+                coords.as_ptr(),
+                coords.len().try_into().unwrap(),
+                shapers.as_ptr(),
+            )
+        };
+        let plan = ShapePlan {
+            raw,
+            face: face.clone(),
+            props: props.into(),
+        };
+        crate::resume_callback_panic();
+        plan
+    }
+
+    /// The name of the shaper the plan uses, e.g. `ot`, or an empty
+    /// string if none of the requested shapers could be used.
+    pub fn shaper(&self) -> &str {
+        unsafe {
+            let shaper = sys::hb_shape_plan_get_shaper(self.raw);
+            if shaper.is_null() {
+                return "";
+            }
+            CStr::from_ptr(shaper).to_str().unwrap_or_default()
+        }
+    }
+
+    /// The segment properties the plan was created for.
+    pub fn segment_properties(&self) -> SegmentProperties {
+        self.props
+    }
+
+    /// Shape `buf` with `font` like [`shape`](crate::shape) does.
+    ///
+    /// `features` should be the features the plan was created with; only
+    /// their ranges are taken from here.
+    ///
+    /// Returns [`Error::ShapePlanMismatch`] if `font` is not of the face
+    /// the plan was created for, or `buf` does not hold Unicode text with
+    /// the segment properties of the plan, and [`Error::ShapingFailed`] if
+    /// the shaper failed.
+    pub fn execute(
+        &self,
+        font: &Font,
+        buf: &mut Buffer,
+        features: &[Feature],
+    ) -> Result<(), Error> {
+        let font_face = unsafe { sys::hb_font_get_face(font.as_ptr() as *mut sys::hb_font_t) };
+        if !std::ptr::eq(font_face, self.face.as_ptr())
+            || buf.segment_properties() != self.props
+            || buf.content_type() == BufferContentType::Glyphs
+        {
+            return Err(Error::ShapePlanMismatch);
+        }
+
+        let ok = unsafe {
+            sys::hb_shape_plan_execute(
+                self.raw,
+                font.as_ptr() as *mut sys::hb_font_t,
+                buf.as_mut_ptr(),
+                features.as_ptr() as *const sys::hb_feature_t,
+                features.len().try_into().unwrap(),
+            )
+        };
+        crate::resume_callback_panic();
+        if ok != 0 {
+            Ok(())
+        } else {
+            Err(Error::ShapingFailed)
+        }
+    }
+
+    /// The indices of the lookups in `table` that the plan applies.
+    pub fn collect_lookups(&self, table: LayoutTable) -> BTreeSet<u32> {
+        let mut set = Set::new();
+        unsafe { sys::hb_ot_shape_plan_collect_lookups(self.raw, table.into(), set.as_mut_ptr()) };
+        crate::resume_callback_panic();
+        set.iter().collect()
+    }
+
+    /// The underlying `hb_shape_plan_t`.
+    pub fn as_ptr(&self) -> *const sys::hb_shape_plan_t {
+        self.raw
+    }
+}
+
+impl Clone for ShapePlan {
+    fn clone(&self) -> ShapePlan {
+        unsafe {
+            ShapePlan {
+                raw: sys::hb_shape_plan_reference(self.raw),
+                face: self.face.clone(),
+                props: self.props,
+            }
+        }
+    }
+}
+
+impl Drop for ShapePlan {
+    fn drop(&mut self) {
+        unsafe {
+            sys::hb_shape_plan_destroy(self.raw);
+        }
+    }
+}
+
+impl std::fmt::Debug for ShapePlan {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("ShapePlan")
+            .field("shaper", &self.shaper())
+            .field("segment_properties", &self.props)
+            .finish()
+    }
+}